@@ -0,0 +1,249 @@
+use crate::modules::Fees;
+use crate::session::{self, Session, Signature};
+use crate::sign_hash;
+use crate::signer;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use web3::types::{Address, Bytes, H256, U256};
+
+pub const BUNDLE_VERSION: u32 = 1;
+
+// a signed relayed transaction, exported to be submitted from another machine
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    pub version: u32,
+    pub chain_id: u64,
+    pub module: Address,
+    pub wallet: Address,
+    pub data: Bytes,
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas_limit: U256,
+    pub signatures: Vec<Signature>,
+}
+
+impl Bundle {
+    pub fn new(session: &Session, chain_id: u64) -> Self {
+        Bundle {
+            version: BUNDLE_VERSION,
+            chain_id,
+            module: session.module,
+            wallet: session.wallet,
+            data: session.data.clone(),
+            nonce: session.nonce,
+            gas_price: session.fees.gas_price,
+            gas_limit: session.fees.gas_limit,
+            signatures: session.signatures.clone(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Bundle, String> {
+        let json = match fs::read_to_string(path) {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to read bundle {}", path)),
+        };
+
+        let bundle: Bundle = match serde_json::from_str(&json) {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("invalid bundle {}", path)),
+        };
+
+        if bundle.version != BUNDLE_VERSION {
+            return Err(format!(
+                "unsupported bundle version {} [expected {}]",
+                bundle.version, BUNDLE_VERSION
+            ));
+        }
+
+        Ok(bundle)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let json = serde_json::to_string_pretty(self).unwrap();
+
+        match fs::write(path, json) {
+            Ok(_) => Ok(()),
+            Err(_e) => Err(format!("unable to write bundle {}", path)),
+        }
+    }
+
+    // structural checks only: the signatures themselves are checked by verify
+    pub fn validate(&self, chain_id: u64) -> Result<(), String> {
+        if self.chain_id != chain_id {
+            return Err(format!(
+                "bundle is meant for chain {} but the node is on chain {}",
+                self.chain_id, chain_id
+            ));
+        }
+
+        if self.data.0.len() < 4 {
+            return Err(String::from("bundle does not call any method"));
+        }
+
+        for (i, signature) in self.signatures.iter().enumerate() {
+            if signature.signature.0.len() != 65 {
+                return Err(format!("invalid signature of {:?}", signature.signer));
+            }
+
+            if self.signatures[..i]
+                .iter()
+                .any(|other| other.signer == signature.signer)
+            {
+                return Err(format!("{:?} has signed twice", signature.signer));
+            }
+        }
+
+        Ok(())
+    }
+
+    // recovers every signature the way the module will: each must come from the signer it claims,
+    // and in the order they are submitted in the owner must come first, then ascending guardians
+    pub fn verify(&self, owner: Address) -> Result<(), String> {
+        let session = self.session();
+        let hash = sign_hash::prefix(H256::from_slice(&session.hash.0));
+
+        for signature in self.signatures.iter() {
+            match signer::recover(hash, &signature.signature.0) {
+                Ok(s) if s == signature.signer => (),
+                Ok(s) => {
+                    return Err(format!(
+                        "signature of {:?} was made by {:?}",
+                        signature.signer, s
+                    ))
+                }
+                Err(e) => return Err(format!("signature of {:?}: {}", signature.signer, e)),
+            }
+        }
+
+        let mut signers = Vec::<Address>::new();
+        for signature in session.signatures(owner).chunks(65) {
+            match signer::recover(hash, signature) {
+                Ok(s) => signers.push(s),
+                Err(e) => return Err(e),
+            }
+        }
+
+        session::ordered(&signers, owner)
+    }
+
+    pub fn session(&self) -> Session {
+        let fees = Fees {
+            gas_price: self.gas_price,
+            gas_limit: self.gas_limit,
+        };
+
+        let hash = sign_hash::sign_hash(
+            self.module,
+            self.wallet,
+            U256::zero(),
+            &self.data.0,
+            self.nonce,
+            fees.gas_price,
+            fees.gas_limit,
+        );
+
+        let mut session = Session::new(
+            self.module,
+            self.wallet,
+            self.data.clone(),
+            self.nonce,
+            fees,
+            Bytes(hash.as_bytes().to_vec()),
+        );
+        session.signatures = self.signatures.clone();
+
+        session
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Bundle, BUNDLE_VERSION};
+    use crate::modules::Fees;
+    use crate::session::Session;
+    use crate::sign_hash;
+    use crate::signer::Key;
+    use std::str::FromStr;
+    use web3::types::{Address, Bytes, H256, H520, U256};
+
+    fn bundle() -> Bundle {
+        let mut session = Session::new(
+            Address::from_str("dfa1468d07fc86840a6eb53e0e65cebde81d1af9").unwrap(),
+            Address::from_str("b1e1c7d9b2a6e4fb9b5bd0e7d6e8c0a1d3f5e7a9").unwrap(),
+            Bytes(vec![0xb0, 0xba, 0x4d, 0xa0]),
+            U256::from(42),
+            Fees {
+                gas_price: U256::zero(),
+                gas_limit: U256::from(250_000),
+            },
+            Bytes(vec![]),
+        );
+        session
+            .add(Address::repeat_byte(1), H520::repeat_byte(1))
+            .unwrap();
+
+        Bundle::new(&session, 1)
+    }
+
+    #[test]
+    fn test_bundle_round_trip() {
+        let bundle = bundle();
+        let json = serde_json::to_string(&bundle).unwrap();
+        let parsed: Bundle = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(parsed.version, BUNDLE_VERSION);
+        assert_eq!(parsed.chain_id, 1);
+        assert_eq!(parsed.nonce, U256::from(42));
+        assert_eq!(parsed.gas_limit, U256::from(250_000));
+        assert_eq!(parsed.signatures.len(), 1);
+        assert!(json.contains("\"gasLimit\""));
+    }
+
+    #[test]
+    fn test_bundle_validation() {
+        let mut bundle = bundle();
+
+        assert!(bundle.validate(1).is_ok());
+        assert!(bundle.validate(3).is_err());
+
+        let signature = bundle.signatures[0].clone();
+        bundle.signatures.push(signature);
+        assert!(bundle.validate(1).is_err());
+    }
+
+    #[test]
+    fn test_bundle_session_hash() {
+        let session = bundle().session();
+
+        assert_eq!(session.hash.0.len(), 32);
+        assert_eq!(session.signatures.len(), 1);
+    }
+
+    #[test]
+    fn test_bundle_verify() {
+        let owner = Key::from_secret(&[0x46; 32]).unwrap();
+        let guardian = Key::from_secret(&[0x47; 32]).unwrap();
+
+        let mut bundle = bundle();
+        bundle.signatures.clear();
+
+        let hash = H256::from_slice(&bundle.session().hash.0);
+        let mut session = bundle.session();
+        for key in [&guardian, &owner].iter() {
+            session
+                .add(key.address(), key.sign(sign_hash::prefix(hash)))
+                .unwrap();
+        }
+        bundle.signatures = session.signatures.clone();
+
+        assert!(bundle.verify(owner.address()).is_ok());
+
+        bundle.signatures[0].signer = Address::repeat_byte(1);
+        assert!(bundle.verify(owner.address()).is_err());
+
+        bundle.signatures[0].signer = guardian.address();
+        bundle.nonce = U256::from(43);
+        assert!(bundle.verify(owner.address()).is_err());
+    }
+}
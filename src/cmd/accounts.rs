@@ -0,0 +1,127 @@
+use crate::helpers;
+use crate::signer::{self, Accounts, Signer};
+use crate::tui;
+use crate::wallet::Wallet;
+use dialoguer::Select;
+use std::process;
+use web3::api::Web3;
+use web3::types::Address;
+
+// the account signing for the wallet: --from, the local key, or one picked among the node
+// accounts
+pub fn signer<'a, T: web3::Transport>(
+    accounts: &Accounts,
+    wallet: Address,
+    web3: &'a Web3<T>,
+) -> Box<dyn Signer + 'a> {
+    if let Some(from) = accounts.from {
+        return available(from, accounts, web3);
+    }
+
+    if let Some(key) = &accounts.key {
+        return signer::of(key.address(), accounts, web3);
+    }
+
+    let candidates = helpers::accounts(web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let account = match candidates.len() {
+        0 => {
+            tui::error(String::from("no account available"));
+            process::exit(1);
+        }
+        1 => candidates[0],
+        _ => pick(&candidates, wallet, web3),
+    };
+
+    signer::of(account, accounts, web3)
+}
+
+// the account sending relayed transactions: --relayer, or the signing account. Offline, only the
+// local key can send them
+pub fn relayer<'a, T: web3::Transport>(
+    accounts: &Accounts,
+    wallet: Address,
+    signer: Option<Address>,
+    web3: &'a Web3<T>,
+) -> Box<dyn Signer + 'a> {
+    match (accounts.relayer, signer) {
+        (Some(relayer), _) => available(relayer, accounts, web3),
+        (None, Some(signer)) if accounts.offline.is_none() => signer::of(signer, accounts, web3),
+        (None, Some(signer)) => available(signer, accounts, web3),
+        (None, None) => self::signer(accounts, wallet, web3),
+    }
+}
+
+fn available<'a, T: web3::Transport>(
+    address: Address,
+    accounts: &Accounts,
+    web3: &'a Web3<T>,
+) -> Box<dyn Signer + 'a> {
+    let local = accounts.key.as_ref().map(|key| key.address()) == Some(address);
+
+    if !local && accounts.offline.is_some() {
+        tui::error(format!(
+            "{:?} is not the local key: it cannot sign offline",
+            address
+        ));
+        process::exit(1);
+    }
+
+    if !local {
+        let candidates = helpers::accounts(web3).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        if !candidates.contains(&address) {
+            tui::error(format!(
+                "{:?} is neither the local key nor an account of the node",
+                address
+            ));
+            process::exit(1);
+        }
+    }
+
+    signer::of(address, accounts, web3)
+}
+
+fn pick<T: web3::Transport>(candidates: &[Address], wallet: Address, web3: &Web3<T>) -> Address {
+    let wallet = Wallet::new(wallet, web3);
+
+    let owner = wallet.owner().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let guardians = wallet.guardians().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let items: Vec<String> = candidates
+        .iter()
+        .map(|account| format!("{:?} | {}", account, role(*account, owner, &guardians)))
+        .collect();
+
+    let index = Select::new()
+        .with_prompt("-[ account to use")
+        .items(&items)
+        .default(0)
+        .interact()
+        .unwrap();
+
+    candidates[index]
+}
+
+pub fn role(account: Address, owner: Address, guardians: &[Address]) -> &'static str {
+    if account == owner {
+        "owner"
+    } else if guardians.contains(&account) {
+        "guardian"
+    } else {
+        "unrelated"
+    }
+}
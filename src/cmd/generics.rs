@@ -1,4 +1,9 @@
+use crate::cmd;
+use crate::cmd::accounts;
+use crate::cmd::lock;
 use crate::helpers;
+use crate::modules::Gas;
+use crate::signer::Accounts;
 use crate::token::Token;
 use crate::tui;
 use crate::wallet::Wallet;
@@ -67,50 +72,94 @@ pub fn balance<T: web3::Transport>(wallet: &str, symbol: &str, web3: Web3<T>) {
     tui::end();
 }
 
-pub fn lock<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+pub fn lock<T: web3::Transport>(
+    wallet: &str,
+    guardian: Option<&str>,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
     let address = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
         tui::error(e);
         process::exit(1);
     });
 
+    if let Some(guardian) = guardian {
+        lock::relay(address, guardian, true, &accounts, gas, dry_run, &web3);
+        return;
+    }
+
+    let signer = accounts::signer(&accounts, address, &web3);
     let wallet = Wallet::new(address, &web3);
 
+    if dry_run {
+        let reason = wallet.simulate_lock(true, &*signer).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        lock::dry_run(reason);
+        return;
+    }
+
     if Confirmation::new()
         .with_text("-[ are you sure you want to lock this wallet?")
         .default(false)
         .interact()
         .unwrap()
     {
-        let tx = wallet.lock().unwrap_or_else(|e| {
+        let tx = wallet.lock(&*signer).unwrap_or_else(|e| {
             tui::error(e);
             process::exit(1);
         });
 
-        tui::header_with_state("lock", "ongoing");
-        tui::info(format!("see https://etherscan.io/tx/{:?}", tx));
+        cmd::tx::report("lock", tx, &accounts);
     }
 }
 
-pub fn unlock<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+pub fn unlock<T: web3::Transport>(
+    wallet: &str,
+    guardian: Option<&str>,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
     let address = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
         tui::error(e);
         process::exit(1);
     });
 
+    if let Some(guardian) = guardian {
+        lock::relay(address, guardian, false, &accounts, gas, dry_run, &web3);
+        return;
+    }
+
+    let signer = accounts::signer(&accounts, address, &web3);
     let wallet = Wallet::new(address, &web3);
 
+    if dry_run {
+        let reason = wallet.simulate_lock(false, &*signer).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        lock::dry_run(reason);
+        return;
+    }
+
     if Confirmation::new()
         .with_text("-[ are you sure you want to unlock this wallet?")
         .default(false)
         .interact()
         .unwrap()
     {
-        let tx = wallet.unlock().unwrap_or_else(|e| {
+        let tx = wallet.unlock(&*signer).unwrap_or_else(|e| {
             tui::error(e);
             process::exit(1);
         });
 
-        tui::header_with_state("unlock", "ongoing");
-        tui::info(format!("see https://etherscan.io/tx/{:?}", tx));
+        cmd::tx::report("unlock", tx, &accounts);
     }
 }
@@ -1,8 +1,16 @@
+use crate::cmd;
+use crate::cmd::accounts;
+use crate::cmd::session;
 use crate::helpers;
+use crate::modules::{Gas, GuardianManager};
+use crate::session::Session;
+use crate::signer::{Accounts, Signer};
 use crate::tui;
-use crate::wallet::Wallet;
+use crate::wallet::{Kind, Wallet};
+use dialoguer::Confirmation;
 use std::process;
 use web3::api::Web3;
+use web3::types::{Address, H256};
 
 pub fn ls<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
     let address = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
@@ -18,10 +26,350 @@ pub fn ls<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
 
     let mut list = Vec::<String>::new();
     for guardian in guardians.iter() {
-        list.push(format!("{:?}", guardian));
+        let ens = helpers::to_ens(*guardian, &web3).unwrap_or_else(|_e| String::from("-"));
+
+        let kind = Wallet::new(*guardian, &web3).kind().unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        let kind = match kind {
+            Kind::Account => String::from("account"),
+            Kind::Contract => String::from("contract"),
+            Kind::Argent(owner) => format!("argent wallet owned by {:?}", owner),
+        };
+
+        list.push(format!("{:?} | {} | {}", guardian, ens, kind));
     }
 
     tui::header("guardians");
     tui::list(&list);
     tui::end();
 }
+
+pub fn pending<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let guardian_manager = GuardianManager::new(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let changes = guardian_manager
+        .pending_changes(wallet)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    let window = guardian_manager.security_window().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let window = window.as_u64();
+
+    let now = helpers::timestamp(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let now = now.as_u64();
+
+    let mut list = Vec::<String>::new();
+    for change in changes.iter() {
+        let opens = change.execute_after;
+        let closes = change.execute_after + window;
+
+        let state = if now <= opens {
+            format!("opens in {}", helpers::duration(opens - now))
+        } else if now < closes {
+            format!("closes in {}", helpers::duration(closes - now))
+        } else {
+            String::from("expired")
+        };
+
+        list.push(format!(
+            "{:?} | {} | {} -> {} | {}",
+            change.guardian,
+            if change.addition {
+                "addition"
+            } else {
+                "revocation"
+            },
+            helpers::date(opens),
+            helpers::date(closes),
+            state
+        ));
+    }
+
+    tui::header("pending guardian changes");
+    tui::list(&list);
+    tui::end();
+}
+
+pub fn add<T: web3::Transport>(
+    wallet: &str,
+    guardian: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    change(
+        wallet,
+        guardian,
+        accounts,
+        gas,
+        dry_run,
+        web3,
+        "-[ are you sure you want to add this guardian?",
+        |guardian_manager, wallet, guardian| {
+            guardian_manager
+                .add_guardian(wallet, guardian)
+                .map(|session| {
+                    security_period(guardian_manager);
+                    session
+                })
+        },
+        requested,
+    );
+}
+
+pub fn revoke<T: web3::Transport>(
+    wallet: &str,
+    guardian: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    change(
+        wallet,
+        guardian,
+        accounts,
+        gas,
+        dry_run,
+        web3,
+        "-[ are you sure you want to revoke this guardian?",
+        |guardian_manager, wallet, guardian| {
+            guardian_manager
+                .revoke_guardian(wallet, guardian)
+                .map(|session| {
+                    security_period(guardian_manager);
+                    session
+                })
+        },
+        requested,
+    );
+}
+
+pub fn confirm<T: web3::Transport>(
+    wallet: &str,
+    guardian: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    let (wallet, guardian) = parse(wallet, guardian, &web3);
+    let relayer = accounts::relayer(&accounts, wallet, None, &web3);
+    let guardian_manager = GuardianManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    let session = guardian_manager
+        .confirm(wallet, guardian)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    session::fees(&session);
+
+    if dry_run {
+        session::dry_run(guardian_manager.simulate(&session));
+        return;
+    }
+
+    if Confirmation::new()
+        .with_text("-[ are you sure you want to confirm the pending change of this guardian?")
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        let tx = guardian_manager.submit(&session).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        cmd::tx::report("guardian change confirmed", tx, &accounts);
+    }
+}
+
+pub fn cancel<T: web3::Transport>(
+    wallet: &str,
+    guardian: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    change(
+        wallet,
+        guardian,
+        accounts,
+        gas,
+        dry_run,
+        web3,
+        "-[ are you sure you want to cancel the pending change of this guardian?",
+        |guardian_manager, wallet, guardian| guardian_manager.cancel(wallet, guardian),
+        |_guardian_manager, _wallet, _guardian, tx, accounts, _web3| {
+            cmd::tx::report("guardian change cancelled", tx, accounts);
+        },
+    );
+}
+
+// a change signed by the owner: the session is built from the guardian manager, shown, signed
+// once confirmed and submitted, the transaction being handed over to report
+#[allow(clippy::too_many_arguments)]
+fn change<T, S, R>(
+    wallet: &str,
+    guardian: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+    prompt: &str,
+    session: S,
+    report: R,
+) where
+    T: web3::Transport,
+    S: FnOnce(&GuardianManager<T>, Address, Address) -> Result<Session, String>,
+    R: FnOnce(&GuardianManager<T>, Address, Address, H256, &Accounts, &Web3<T>),
+{
+    let (wallet, guardian) = parse(wallet, guardian, &web3);
+    let signer = accounts::signer(&accounts, wallet, &web3);
+    let relayer = accounts::relayer(&accounts, wallet, Some(signer.address()), &web3);
+    let guardian_manager = GuardianManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    let mut session = session(&guardian_manager, wallet, guardian).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    session::fees(&session);
+
+    if dry_run {
+        sign(&guardian_manager, &mut session, &*signer);
+        session::dry_run(guardian_manager.simulate(&session));
+        return;
+    }
+
+    if Confirmation::new()
+        .with_text(prompt)
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        sign(&guardian_manager, &mut session, &*signer);
+
+        let tx = guardian_manager.submit(&session).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        report(&guardian_manager, wallet, guardian, tx, &accounts, &web3);
+    }
+}
+
+fn parse<T: web3::Transport>(wallet: &str, guardian: &str, web3: &Web3<T>) -> (Address, Address) {
+    let wallet = helpers::to_address(wallet, web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let guardian = helpers::to_address(guardian, web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    (wallet, guardian)
+}
+
+fn sign<T: web3::Transport>(
+    guardian_manager: &GuardianManager<T>,
+    session: &mut Session,
+    signer: &dyn Signer,
+) {
+    guardian_manager.sign(session, signer).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+}
+
+fn security_period<T: web3::Transport>(guardian_manager: &GuardianManager<T>) {
+    let period = guardian_manager.security_period().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    tui::header("security period");
+    tui::info(helpers::duration(period.as_u64()));
+    tui::end();
+}
+
+fn requested<T: web3::Transport>(
+    guardian_manager: &GuardianManager<T>,
+    wallet: Address,
+    guardian: Address,
+    tx: H256,
+    accounts: &Accounts,
+    web3: &Web3<T>,
+) {
+    if !cmd::tx::report("guardian change requested", tx, accounts) {
+        return;
+    }
+
+    helpers::receipt(tx, web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let change = guardian_manager
+        .pending_change(wallet, guardian)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    match change {
+        Some(change) => {
+            let window = guardian_manager.security_window().unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+
+            tui::header_with_state("guardian change requested", "pending");
+            tui::header("confirmation window");
+            tui::info(format!(
+                "{} -> {}",
+                helpers::date(change.execute_after),
+                helpers::date(change.execute_after + window.as_u64())
+            ));
+        }
+        None => tui::header_with_state("guardian change requested", "done"),
+    }
+
+    tui::end();
+}
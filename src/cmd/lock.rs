@@ -0,0 +1,220 @@
+use crate::cmd;
+use crate::cmd::accounts;
+use crate::cmd::session;
+use crate::helpers;
+use crate::modules;
+use crate::modules::{Gas, LockManager};
+use crate::signer::{self, Accounts};
+use crate::tui;
+use crate::wallet::Wallet;
+use dialoguer::Confirmation;
+use std::process;
+use web3::api::Web3;
+use web3::types::Address;
+
+pub fn status<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+    let address = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let wallet = Wallet::new(address, &web3);
+
+    let status = wallet.lock_status().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    if !status.locked {
+        tui::header_with_state("lock", "unlocked");
+        tui::end();
+        return;
+    }
+
+    let now = helpers::timestamp(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let now = now.as_u64();
+
+    tui::header_with_state("lock", "locked");
+    tui::header("expires");
+    tui::info(format!(
+        "{} [{} left]",
+        helpers::date(status.release_after),
+        helpers::duration(status.release_after.saturating_sub(now))
+    ));
+    tui::header("locked by");
+    tui::info(format!(
+        "{:?} | {}",
+        status.locker,
+        modules::name(&status.locker)
+    ));
+    tui::end();
+}
+
+// locks or unlocks a wallet with the signature of one of its guardians, the transaction being
+// sent by the relaying account
+pub fn relay<T: web3::Transport>(
+    wallet: Address,
+    guardian: &str,
+    lock: bool,
+    accounts: &Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: &Web3<T>,
+) {
+    let operation = if lock { "lock" } else { "unlock" };
+
+    let guardian = helpers::to_address(guardian, web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let relayer = accounts::relayer(accounts, wallet, None, web3);
+    let guardian = signer::of(guardian, accounts, web3);
+
+    let lock_manager = LockManager::new(web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    let session = if lock {
+        lock_manager.lock(wallet)
+    } else {
+        lock_manager.unlock(wallet)
+    };
+    let mut session = session.unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    session::fees(&session);
+
+    if dry_run {
+        lock_manager
+            .sign(&mut session, &*guardian)
+            .unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+
+        session::dry_run(lock_manager.simulate(&session));
+        return;
+    }
+
+    if Confirmation::new()
+        .with_text(&format!(
+            "-[ are you sure you want to {} this wallet with the signature of {:?}?",
+            operation,
+            guardian.address()
+        ))
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        lock_manager
+            .sign(&mut session, &*guardian)
+            .unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+
+        let tx = lock_manager.submit(&session).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        cmd::tx::report(operation, tx, accounts);
+    }
+}
+
+// outcome of a direct lock or unlock: the revert reason, if any
+pub fn dry_run(reason: Option<String>) {
+    match reason {
+        Some(reason) => {
+            tui::header_with_state("dry run", "reverted");
+            tui::info(reason);
+        }
+        None => tui::header_with_state("dry run", "success"),
+    }
+    tui::end();
+}
+
+pub fn history<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let events = LockManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .history(wallet)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    let now = helpers::timestamp(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let now = now.as_u64();
+
+    let mut list = Vec::<String>::new();
+    for (i, event) in events.iter().enumerate() {
+        let mut entry = format!(
+            "{} | #{} | {} | {}",
+            helpers::date(event.timestamp),
+            event.block,
+            if event.locked { "locked" } else { "unlocked" },
+            modules::name(&event.module)
+        );
+
+        if let Some(release_after) = event.release_after {
+            // a lock lasts until it expires or until the wallet is explicitly unlocked
+            let unlocked = events[i + 1..]
+                .iter()
+                .find(|next| !next.locked)
+                .map(|next| next.timestamp);
+            let end = match unlocked {
+                Some(s) if s < release_after => s,
+                _ => release_after,
+            };
+
+            // the lock period of the block could not be read: the current one stands in for it
+            let estimated = if event.estimated { " (estimated)" } else { "" };
+
+            entry.push_str(&format!(
+                " | until {}{}",
+                helpers::date(release_after),
+                estimated
+            ));
+            if end > now {
+                entry.push_str(&format!(
+                    " | ongoing, {} left{}",
+                    helpers::duration(end - now),
+                    estimated
+                ));
+            } else {
+                entry.push_str(&format!(
+                    " | lasted {}{}",
+                    helpers::duration(end.saturating_sub(event.timestamp)),
+                    estimated
+                ));
+            }
+        }
+
+        entry.push_str(&format!(" | from {:?} | {:?}", event.sender, event.tx));
+        list.push(entry);
+    }
+
+    tui::header("history");
+    tui::list(&list);
+    tui::end();
+}
@@ -0,0 +1,53 @@
+use crate::cmd::accounts;
+use crate::helpers;
+use crate::signer::mnemonic;
+use crate::tui;
+use crate::wallet::Wallet;
+use std::process;
+use web3::api::Web3;
+
+// lists the accounts derived next to the given path, with their role in the wallet
+pub fn ls<T: web3::Transport>(wallet: &str, seed: &[u8], path: &str, count: u32, web3: Web3<T>) {
+    let address = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let wallet = Wallet::new(address, &web3);
+
+    let owner = wallet.owner().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let guardians = wallet.guardians().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let first = mnemonic::index(path).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let mut list = Vec::<String>::new();
+    for index in first..first.saturating_add(count) {
+        let path = mnemonic::sibling(path, index).unwrap();
+        let account = mnemonic::derive(seed, &path)
+            .unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            })
+            .address();
+
+        list.push(format!(
+            "{} | {:?} | {}",
+            path,
+            account,
+            accounts::role(account, owner, &guardians)
+        ));
+    }
+
+    tui::header("derived accounts");
+    tui::list(&list);
+    tui::end();
+}
@@ -1,4 +1,12 @@
+pub mod accounts;
 pub mod generics;
 pub mod guardians;
+pub mod lock;
+pub mod mnemonic;
 pub mod modules;
+pub mod owner;
 pub mod recovery;
+pub mod relay;
+pub mod session;
+pub mod signature;
+pub mod tx;
@@ -20,11 +20,7 @@ pub fn ls<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
     });
 
     for module in modules.iter() {
-        list.push(format!(
-            "{:?} | {}",
-            module,
-            modules::name(&module).unwrap()
-        ));
+        list.push(format!("{:?} | {}", module, modules::name(&module)));
     }
 
     tui::header("modules");
@@ -0,0 +1,207 @@
+use crate::cmd;
+use crate::cmd::accounts;
+use crate::cmd::session;
+use crate::helpers;
+use crate::modules::{Gas, RecoveryManager};
+use crate::signer::Accounts;
+use crate::tui;
+use dialoguer::Confirmation;
+use std::process;
+use web3::api::Web3;
+
+pub fn transfer<T: web3::Transport>(
+    wallet: &str,
+    owner: &str,
+    reset: bool,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let owner = helpers::to_address(owner, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let signer = accounts::signer(&accounts, wallet, &web3);
+    let relayer = accounts::relayer(&accounts, wallet, Some(signer.address()), &web3);
+
+    let recovery_manager = RecoveryManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    if reset {
+        session::discard(recovery_manager.address, wallet);
+    }
+
+    let mut session = recovery_manager
+        .transfer_ownership(wallet, owner)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    if let Some(tx) = session::collect(
+        &recovery_manager,
+        &mut session,
+        &*signer,
+        &accounts,
+        "ownership transfer",
+        dry_run,
+        None,
+    ) {
+        if !cmd::tx::report("ownership transfer", tx, &accounts) {
+            return;
+        }
+
+        let (owner, execute_after) = recovery_manager
+            .ownership_transfer_executed(tx)
+            .unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+
+        tui::header_with_state("ownership transfer", "executed");
+        tui::header("new owner");
+        tui::address(owner);
+        tui::header("finalizable after");
+        tui::info(helpers::date(execute_after));
+        tui::end();
+    }
+}
+
+pub fn finalize<T: web3::Transport>(
+    wallet: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let relayer = accounts::relayer(&accounts, wallet, None, &web3);
+
+    let recovery_manager = RecoveryManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    let session = recovery_manager
+        .finalize_ownership_transfer(wallet)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    session::fees(&session);
+
+    if dry_run {
+        session::dry_run(recovery_manager.simulate(&session));
+        return;
+    }
+
+    if Confirmation::new()
+        .with_text("-[ are you sure you want to finalize the ownership transfer of this wallet?")
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        let tx = recovery_manager.submit(&session).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        if !cmd::tx::report("ownership transfer finalized", tx, &accounts) {
+            return;
+        }
+
+        let owner = recovery_manager
+            .ownership_transfer_finalized(tx)
+            .unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+
+        tui::header_with_state("ownership transfer finalized", "done");
+        tui::header("owner");
+        tui::address(owner);
+        tui::end();
+    }
+}
+
+pub fn cancel<T: web3::Transport>(
+    wallet: &str,
+    reset: bool,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let signer = accounts::signer(&accounts, wallet, &web3);
+    let relayer = accounts::relayer(&accounts, wallet, Some(signer.address()), &web3);
+
+    let recovery_manager = RecoveryManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    if reset {
+        session::discard(recovery_manager.address, wallet);
+    }
+
+    let mut session = recovery_manager
+        .cancel_ownership_transfer(wallet)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    if let Some(tx) = session::collect(
+        &recovery_manager,
+        &mut session,
+        &*signer,
+        &accounts,
+        "ownership transfer cancellation",
+        dry_run,
+        None,
+    ) {
+        if !cmd::tx::report("ownership transfer cancelled", tx, &accounts) {
+            return;
+        }
+
+        let owner = recovery_manager
+            .ownership_transfer_canceled(tx)
+            .unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+
+        tui::header_with_state("ownership transfer cancelled", "done");
+        tui::header("cancelled owner");
+        tui::address(owner);
+        tui::end();
+    }
+}
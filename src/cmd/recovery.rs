@@ -1,12 +1,27 @@
+use crate::cmd;
+use crate::cmd::accounts;
+use crate::cmd::session;
 use crate::helpers;
-use crate::modules::RecoveryManager;
+use crate::modules::{Gas, RecoveryManager};
+use crate::signer::Accounts;
 use crate::tui;
 
 use dialoguer::Confirmation;
 use std::process;
 use web3::api::Web3;
+use web3::types::Address;
 
-pub fn init<T: web3::Transport>(wallet: &str, owner: &str, web3: Web3<T>) {
+#[allow(clippy::too_many_arguments)]
+pub fn init<T: web3::Transport>(
+    wallet: &str,
+    owner: &str,
+    reset: bool,
+    export: Option<&str>,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
     let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
         tui::error(e);
         process::exit(1);
@@ -17,65 +32,240 @@ pub fn init<T: web3::Transport>(wallet: &str, owner: &str, web3: Web3<T>) {
         process::exit(1);
     });
 
-    let recovery_manager = RecoveryManager::new(&web3);
+    let signer = accounts::signer(&accounts, wallet, &web3);
+    let relayer = accounts::relayer(&accounts, wallet, Some(signer.address()), &web3);
 
-    if Confirmation::new()
-        .with_text("-[ are you sure you want to initialize the recovery of this wallet?")
-        .default(false)
-        .interact()
-        .unwrap()
-    {
-        let tx = recovery_manager
-            .initialize(wallet, owner)
-            .unwrap_or_else(|e| {
-                tui::error(e);
-                process::exit(1);
-            });
-
-        tui::header_with_state("recovery initialized", "ongoing");
-        tui::info(format!("see https://etherscan.io/tx/{:?}", tx));
+    let recovery_manager = RecoveryManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    if reset {
+        session::discard(recovery_manager.address, wallet);
+    }
+
+    let mut session = recovery_manager
+        .initialize(wallet, owner)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    if let Some(tx) = session::collect(
+        &recovery_manager,
+        &mut session,
+        &*signer,
+        &accounts,
+        "recovery",
+        dry_run,
+        export,
+    ) {
+        cmd::tx::report("recovery initialized", tx, &accounts);
+    }
+}
+
+pub fn cancel<T: web3::Transport>(
+    wallet: &str,
+    reset: bool,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let signer = accounts::signer(&accounts, wallet, &web3);
+    let relayer = accounts::relayer(&accounts, wallet, Some(signer.address()), &web3);
+
+    let recovery_manager = RecoveryManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    if reset {
+        session::discard(recovery_manager.address, wallet);
+    }
+
+    let mut session = recovery_manager
+        .cancel_recovery(wallet)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    if let Some(tx) = session::collect(
+        &recovery_manager,
+        &mut session,
+        &*signer,
+        &accounts,
+        "recovery cancellation",
+        dry_run,
+        None,
+    ) {
+        cmd::tx::report("recovery cancelled", tx, &accounts);
     }
 }
 
-pub fn cancel<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+pub fn finalize<T: web3::Transport>(
+    wallet: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
     let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
         tui::error(e);
         process::exit(1);
     });
 
-    let recovery_manager = RecoveryManager::new(&web3);
+    let relayer = accounts::relayer(&accounts, wallet, None, &web3);
+
+    let recovery_manager = RecoveryManager::new(&web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    let session = recovery_manager
+        .finalize_recovery(wallet)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    session::fees(&session);
+
+    if dry_run {
+        session::dry_run(recovery_manager.simulate(&session));
+        return;
+    }
 
     if Confirmation::new()
-        .with_text("-[ are you sure you want to cancel the recovery of this wallet?")
+        .with_text("-[ are you sure you want to finalize the recovery of this wallet?")
         .default(false)
         .interact()
         .unwrap()
     {
-        let tx = recovery_manager
-            .cancel_recovery(wallet)
-            .unwrap_or_else(|e| {
-                tui::error(e);
-                process::exit(1);
-            });
-
-        tui::header_with_state("recovery cancelled", "ongoing");
-        tui::info(format!("see https://etherscan.io/tx/{:?}", tx));
-    };
-
-    // tui::header("guardians");
-    // tui::list(&list);
-    // tui::end();
+        let tx = recovery_manager.submit(&session).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        if !cmd::tx::report("recovery finalized", tx, &accounts) {
+            return;
+        }
+
+        let owner = recovery_manager.recovery_finalized(tx).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        tui::header_with_state("recovery finalized", "done");
+        tui::header("owner");
+        tui::address(owner);
+        tui::end();
+    }
 }
 
-pub fn finalize<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
-    let _address = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+pub fn status<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
         tui::error(e);
         process::exit(1);
     });
 
-    println!("finalize {}", wallet);
+    let recovery_manager = RecoveryManager::new(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let status = recovery_manager.status(wallet).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let now = helpers::timestamp(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+    let now = now.as_u64();
+
+    if status.recovery == Address::zero() {
+        tui::header_with_state("recovery", "none");
+    } else {
+        tui::header_with_state("recovery", "pending");
+        tui::header("new owner");
+        tui::address(status.recovery);
+        tui::header("guardians at recovery");
+        tui::info(format!("{}", status.guardian_count));
+        tui::header("finalizable after");
+        if status.execute_after > now {
+            tui::info(format!(
+                "{} [{} left]",
+                helpers::date(status.execute_after),
+                helpers::duration(status.execute_after - now)
+            ));
+        } else {
+            tui::info(format!(
+                "{} [finalizable now]",
+                helpers::date(status.execute_after)
+            ));
+        }
+    }
+
+    tui::header("locked by recovery");
+    tui::info(format!("{}", status.locked));
+    tui::header("recovery period");
+    tui::info(helpers::duration(status.recovery_period.as_u64()));
+    tui::header("lock period");
+    tui::info(helpers::duration(status.lock_period.as_u64()));
+    tui::end();
+}
+
+pub fn history<T: web3::Transport>(wallet: &str, web3: Web3<T>) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let recovery_manager = RecoveryManager::new(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let events = recovery_manager.history(wallet).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let mut list = Vec::<String>::new();
+    for event in events.iter() {
+        let mut entry = format!(
+            "{} | #{} | {} | {:?}",
+            helpers::date(event.timestamp),
+            event.block,
+            event.name,
+            event.owner
+        );
+        if let Some(execute_after) = event.execute_after {
+            entry.push_str(&format!(" | until {}", helpers::date(execute_after)));
+        }
+        entry.push_str(&format!(" | from {:?} | tx {:?}", event.sender, event.tx));
+
+        list.push(entry);
+    }
 
-    // tui::header("guardians");
-    // tui::list(&list);
-    // tui::end();
+    tui::header("history");
+    tui::list(&list);
+    tui::end();
 }
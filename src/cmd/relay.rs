@@ -0,0 +1,128 @@
+use crate::bundle::Bundle;
+use crate::cmd;
+use crate::cmd::accounts;
+use crate::cmd::session;
+use crate::constants;
+use crate::helpers;
+use crate::modules::{self, Gas, RelayedModule};
+use crate::signer::Accounts;
+use crate::tui;
+use crate::wallet::Wallet;
+use dialoguer::Confirmation;
+use std::process;
+use web3::api::Web3;
+use web3::types::U256;
+
+pub fn nonce<T: web3::Transport>(wallet: &str, module: &str, web3: Web3<T>) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let module = modules::address(module).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let nonce = modules::nonce(module, wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let counter = nonce & ((U256::one() << 128) - 1);
+
+    tui::header("module");
+    tui::info(format!("{:?} | {}", module, modules::name(&module)));
+    tui::header("next nonce");
+    tui::info(format!("{:#x}", nonce));
+    tui::info(format!("block #{} | counter {}", nonce >> 128, counter));
+    tui::end();
+}
+
+pub fn submit<T: web3::Transport>(
+    path: &str,
+    accounts: Accounts,
+    gas: Gas,
+    dry_run: bool,
+    web3: Web3<T>,
+) {
+    let bundle = Bundle::load(path).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let chain_id = helpers::chain_id(&web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    bundle.validate(chain_id).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let relayer = accounts::relayer(&accounts, bundle.wallet, None, &web3);
+
+    // submitting only needs the relayer interface, whatever the module is
+    let module = RelayedModule::new(bundle.module, constants::abis::RELAYER_MODULE, &web3)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+        .with_gas(gas)
+        .with_relayer(&*relayer);
+
+    let owner = Wallet::new(bundle.wallet, &web3)
+        .owner()
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    bundle.verify(owner).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let session = bundle.session();
+
+    let mut list = Vec::<String>::new();
+    for signer in session.signers().iter() {
+        list.push(format!("{:?}", signer));
+    }
+
+    tui::header("module");
+    tui::info(format!(
+        "{:?} | {}",
+        bundle.module,
+        modules::name(&bundle.module)
+    ));
+    tui::header("wallet");
+    tui::address(bundle.wallet);
+    tui::header("nonce");
+    tui::info(format!("{:#x}", bundle.nonce));
+    tui::header_with_state("signatures", &format!("{}", list.len()));
+    tui::list(&list);
+    tui::end();
+
+    session::fees(&session);
+
+    if dry_run {
+        session::dry_run(module.simulate(&session, owner));
+        return;
+    }
+
+    if Confirmation::new()
+        .with_text("-[ are you sure you want to submit this bundle?")
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        let tx = module.submit(&session, owner).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        cmd::tx::report("bundle submitted", tx, &accounts);
+    }
+}
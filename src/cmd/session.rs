@@ -0,0 +1,206 @@
+use crate::bundle::Bundle;
+use crate::modules::{Outcome, RecoveryManager};
+use crate::network;
+use crate::session::Session;
+use crate::signer::{Accounts, Signer};
+use crate::token::Token;
+use crate::tui;
+use dialoguer::Confirmation;
+use std::process;
+use web3::types::{Address, H256};
+
+pub fn discard(module: Address, wallet: Address) {
+    let session = Session::load(module, wallet).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    if let Some(session) = session {
+        session.discard().unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+    }
+}
+
+pub fn collect<T: web3::Transport>(
+    recovery_manager: &RecoveryManager<T>,
+    session: &mut Session,
+    signer: &dyn Signer,
+    accounts: &Accounts,
+    operation: &str,
+    dry_run: bool,
+    export: Option<&str>,
+) -> Option<H256> {
+    // signing commits to the fees: they are shown before
+    if !session.signers().contains(&signer.address()) {
+        fees(session);
+
+        if Confirmation::new()
+            .with_text(&format!(
+                "-[ are you sure you want to sign the {} with {:?}?",
+                operation,
+                signer.address()
+            ))
+            .default(false)
+            .interact()
+            .unwrap()
+        {
+            recovery_manager.sign(session, signer).unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+
+            session.save().unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            });
+        }
+    }
+
+    let required = recovery_manager
+        .required_signatures(session)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    let mut list = Vec::<String>::new();
+    for signer in session.signers().iter() {
+        list.push(format!("{:?}", signer));
+    }
+
+    tui::header_with_state(
+        "signatures",
+        &format!("{}/{}", session.signatures.len(), required),
+    );
+    tui::list(&list);
+
+    if let Some(path) = export {
+        self::export(session, path);
+
+        if session.signatures.len() < required {
+            tui::info(String::from(
+                "the bundle lacks signatures: it will not go through until they are collected",
+            ));
+        }
+        tui::end();
+        return None;
+    }
+
+    if session.signatures.len() < required {
+        tui::info(String::from(
+            "run this command again with another account to add a signature",
+        ));
+        tui::end();
+        return None;
+    }
+
+    fees(session);
+
+    if dry_run {
+        self::dry_run(recovery_manager.simulate(session));
+        return None;
+    }
+
+    if Confirmation::new()
+        .with_text(&format!(
+            "-[ are you sure you want to submit the {} of this wallet?",
+            operation
+        ))
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        let tx = recovery_manager.submit(session).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        // a transaction signed offline may never make it on chain: the signatures are kept until
+        // it has been broadcast
+        if accounts.offline.is_some() {
+            tui::info(String::from(
+                "signatures are kept: once argent tx broadcast succeeds, discard them with --reset",
+            ));
+            return Some(tx);
+        }
+
+        session.discard().unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        return Some(tx);
+    }
+
+    None
+}
+
+// bundles are meant for the chain of the selected network, so that air-gapped machines can export
+// them
+pub fn export(session: &Session, path: &str) {
+    let chain_id = network::current().chain_id;
+
+    Bundle::new(session, chain_id)
+        .save(path)
+        .unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    tui::header_with_state("bundle", "exported");
+    tui::info(format!("{} | chain {}", path, chain_id));
+}
+
+pub fn fees(session: &Session) {
+    let eth = Token::from_symbol("ETH").unwrap();
+    let gwei = Token {
+        symbol: String::from("gwei"),
+        decimals: 9,
+        ..eth.clone()
+    };
+
+    tui::header("gas limit");
+    tui::info(format!("{}", session.fees.gas_limit));
+
+    if session.fees.gas_price.is_zero() {
+        tui::header_with_state("refund", "none");
+    } else {
+        tui::header("gas price");
+        tui::info(format!("{} gwei", gwei.to_decimals(session.fees.gas_price)));
+        tui::header("maximum refund");
+        tui::info(format!("{} ETH", eth.to_decimals(session.fees.refund())));
+    }
+    tui::end();
+}
+
+pub fn dry_run(outcome: Result<Outcome, String>) {
+    let outcome = outcome.unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    match outcome {
+        Outcome::Executed {
+            success,
+            sign_hash,
+            reason,
+        } => {
+            tui::header_with_state("dry run", if success { "success" } else { "failure" });
+            tui::info(format!(
+                "TransactionExecuted | success {} | signHash {:?}",
+                success, sign_hash
+            ));
+            if let Some(reason) = reason {
+                tui::header("reason");
+                tui::info(reason);
+            }
+        }
+        Outcome::Reverted(reason) => {
+            tui::header_with_state("dry run", "reverted");
+            tui::info(reason);
+        }
+    }
+    tui::end();
+}
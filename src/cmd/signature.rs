@@ -0,0 +1,129 @@
+use crate::cmd::accounts;
+use crate::constants;
+use crate::helpers;
+use crate::modules::{self, Fees, RelayedModule};
+use crate::session;
+use crate::sign_hash;
+use crate::signer;
+use crate::tui;
+use crate::wallet::Wallet;
+use std::process;
+use std::str::FromStr;
+use web3::api::Web3;
+use web3::types::U256;
+
+// recovers the signers of a relayed transaction locally, the way the module would, before it is
+// submitted to any relayer. The fees are part of the sign hash: they must be the signed ones
+pub fn verify<T: web3::Transport>(
+    wallet: &str,
+    module: &str,
+    data: &str,
+    nonce: &str,
+    signatures: &str,
+    fees: Fees,
+    web3: Web3<T>,
+) {
+    let wallet = helpers::to_address(wallet, &web3).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let module = modules::address(module).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let data = hex::decode(data.trim_start_matches("0x")).unwrap_or_else(|_e| {
+        tui::error(String::from("invalid data"));
+        process::exit(1);
+    });
+
+    let parsed = if nonce.starts_with("0x") {
+        U256::from_str(nonce.trim_start_matches("0x")).ok()
+    } else {
+        U256::from_dec_str(nonce).ok()
+    };
+    let nonce = parsed.unwrap_or_else(|| {
+        tui::error(format!("invalid nonce {}", nonce));
+        process::exit(1);
+    });
+
+    let signatures = hex::decode(signatures.trim_start_matches("0x")).unwrap_or_else(|_e| {
+        tui::error(String::from("invalid signatures"));
+        process::exit(1);
+    });
+
+    if signatures.is_empty() || signatures.len() % 65 != 0 {
+        tui::error(String::from(
+            "signatures must be a concatenation of 65 bytes signatures",
+        ));
+        process::exit(1);
+    }
+
+    // the relayer interface is enough to rebuild the sign hash
+    let module =
+        RelayedModule::new(module, constants::abis::RELAYER_MODULE, &web3).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+    let hash = module.sign_hash(wallet, &data, nonce, &fees);
+
+    let wallet = Wallet::new(wallet, &web3);
+
+    let owner = wallet.owner().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let guardians = wallet.guardians().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    tui::header("module");
+    tui::info(format!(
+        "{:?} | {}",
+        module.address,
+        modules::name(&module.address)
+    ));
+    tui::header("sign hash");
+    tui::info(format!("{:?}", hash));
+    tui::info(format!(
+        "gas price {} | gas limit {}",
+        fees.gas_price, fees.gas_limit
+    ));
+
+    let mut signers = Vec::new();
+    let mut entries = Vec::new();
+    for signature in signatures.chunks(65) {
+        match signer::recover(sign_hash::prefix(hash), signature) {
+            Ok(s) => {
+                entries.push(format!(
+                    "{:?} | {}",
+                    s,
+                    accounts::role(s, owner, &guardians)
+                ));
+                signers.push(s);
+            }
+            Err(e) => entries.push(e),
+        }
+    }
+
+    tui::header("signers");
+    tui::list(&entries);
+
+    if signers.len() < entries.len() {
+        tui::header_with_state("ordering", "unknown");
+    } else {
+        match session::ordered(&signers, owner) {
+            Ok(_) => tui::header_with_state("ordering", "valid"),
+            Err(e) => {
+                tui::header_with_state("ordering", "invalid");
+                tui::info(e);
+            }
+        }
+    }
+
+    tui::end();
+}
@@ -0,0 +1,67 @@
+use crate::network;
+use crate::signer::Accounts;
+use crate::tui;
+use dialoguer::Confirmation;
+use std::process;
+use web3::api::Web3;
+use web3::futures::Future;
+use web3::types::{Bytes, H256};
+
+// broadcasts a raw transaction, as signed offline
+pub fn broadcast<T: web3::Transport>(raw: &str, web3: Web3<T>) {
+    let raw = hex::decode(raw.trim().trim_start_matches("0x")).unwrap_or_else(|_e| {
+        tui::error(String::from("invalid raw transaction"));
+        process::exit(1);
+    });
+
+    if Confirmation::new()
+        .with_text("-[ are you sure you want to broadcast this transaction?")
+        .default(false)
+        .interact()
+        .unwrap()
+    {
+        let tx = web3
+            .eth()
+            .send_raw_transaction(Bytes(raw))
+            .wait()
+            .unwrap_or_else(|e| {
+                tui::error(format!("unable to broadcast transaction: {}", e));
+                process::exit(1);
+            });
+
+        tui::header_with_state("transaction", "broadcast");
+        link(tx);
+        tui::end();
+    }
+}
+
+// a transaction sent on behalf of an operation: signed offline, it is only printed and there is
+// nothing to wait for until it is broadcast. Whether it was sent
+pub fn report(operation: &str, tx: H256, accounts: &Accounts) -> bool {
+    if accounts.offline.is_some() {
+        tui::header_with_state(operation, "signed");
+        tui::info(format!(
+            "tx {:?} | broadcast it with argent tx broadcast",
+            tx
+        ));
+        tui::end();
+        return false;
+    }
+
+    tui::header_with_state(operation, "ongoing");
+    link(tx);
+
+    true
+}
+
+// link to a transaction on the explorer of the network, or its bare hash when there is none
+pub fn link(tx: H256) {
+    match network::current().explorer {
+        Some(explorer) => tui::info(format!(
+            "see {}/tx/{:?}",
+            explorer.trim_end_matches('/'),
+            tx
+        )),
+        None => tui::info(format!("tx {:?}", tx)),
+    }
+}
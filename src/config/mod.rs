@@ -0,0 +1,209 @@
+use crate::constants;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+pub const RPC_ENV: &str = "ARGENT_RPC";
+
+// ~/.argent/config.json, every entry being optional
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub rpc: Option<String>,
+    pub headers: BTreeMap<String, String>,
+    pub auth: Option<String>,
+}
+
+impl Config {
+    pub fn load() -> Result<Config, String> {
+        let path = match path() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        if !path.exists() {
+            return Ok(Config::default());
+        }
+
+        let json = match fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to read config {:?}", path)),
+        };
+
+        match serde_json::from_str(&json) {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!("invalid config {:?}: {}", path, e)),
+        }
+    }
+}
+
+// node to connect to, along with whatever headers it requires
+#[derive(Clone, Debug, PartialEq)]
+pub struct Endpoint {
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+}
+
+// --rpc first, then ARGENT_RPC, then the config file and finally Frame. Headers and auth of the
+// config file belong to its node: they are only sent to it, overridden by the ones given on the
+// command line
+pub fn endpoint(
+    rpc: Option<&str>,
+    headers: &[&str],
+    auth: Option<&str>,
+    config: &Config,
+) -> Result<Endpoint, String> {
+    let from_env = env::var(RPC_ENV).ok().filter(|url| !url.is_empty());
+
+    let (url, configured) = match (rpc, from_env, &config.rpc) {
+        (Some(s), _, _) => (s.to_string(), false),
+        (None, Some(s), _) => (s, false),
+        (None, None, Some(s)) => (s.clone(), true),
+        (None, None, None) => (constants::endpoints::FRAME.to_string(), false),
+    };
+
+    let mut merged = if configured {
+        config.headers.clone()
+    } else {
+        BTreeMap::new()
+    };
+
+    for header in headers {
+        match header.find(':') {
+            Some(index) => merged.insert(
+                header[..index].trim().to_string(),
+                header[index + 1..].trim().to_string(),
+            ),
+            None => return Err(format!("invalid header {}: expected name: value", header)),
+        };
+    }
+
+    let auth = match (auth, &config.auth) {
+        (Some(s), _) => Some(s),
+        (None, Some(s)) if configured => Some(s.as_str()),
+        _ => None,
+    };
+
+    if let Some(auth) = auth {
+        match authorization(auth) {
+            Ok(s) => merged.insert(String::from("Authorization"), s),
+            Err(e) => return Err(e),
+        };
+    }
+
+    Ok(Endpoint {
+        url,
+        headers: merged.into_iter().collect(),
+    })
+}
+
+// "basic <user>:<password>" or "bearer <token>", as the Authorization header expects them
+fn authorization(auth: &str) -> Result<String, String> {
+    let mut parts = auth.trim().splitn(2, ' ');
+    let scheme = parts.next().unwrap_or_default().to_lowercase();
+    let credentials = parts.next().unwrap_or_default().trim();
+
+    match (scheme.as_str(), credentials) {
+        (_, "") => Err(String::from(
+            "invalid auth: expected basic <user>:<password> or bearer <token>",
+        )),
+        ("basic", s) if s.contains(':') => Ok(format!("Basic {}", base64::encode(s))),
+        ("bearer", s) => Ok(format!("Bearer {}", s)),
+        _ => Err(String::from(
+            "invalid auth: expected basic <user>:<password> or bearer <token>",
+        )),
+    }
+}
+
+fn path() -> Result<PathBuf, String> {
+    let home = match env::var("HOME") {
+        Ok(s) => s,
+        Err(_e) => return Err(String::from("unable to locate home directory")),
+    };
+
+    let mut path = PathBuf::from(home);
+    path.push(constants::CONFIG_FILE);
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{authorization, endpoint, Config};
+
+    #[test]
+    fn test_authorization() {
+        assert_eq!(
+            authorization("basic alice:secret").unwrap(),
+            "Basic YWxpY2U6c2VjcmV0"
+        );
+        assert_eq!(authorization("Bearer abc").unwrap(), "Bearer abc");
+        assert!(authorization("basic alice").is_err());
+        assert!(authorization("digest abc").is_err());
+        assert!(authorization("bearer").is_err());
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let mut config = Config {
+            rpc: Some(String::from("http://config:8545")),
+            auth: Some(String::from("bearer config")),
+            ..Config::default()
+        };
+        config
+            .headers
+            .insert(String::from("X-Api-Key"), String::from("config"));
+        config
+            .headers
+            .insert(String::from("X-Team"), String::from("rescue"));
+
+        let endpoint = endpoint(None, &["X-Api-Key: flag"], None, &config).unwrap();
+
+        assert_eq!(endpoint.url, "http://config:8545");
+        assert_eq!(
+            endpoint.headers,
+            vec![
+                (String::from("Authorization"), String::from("Bearer config")),
+                (String::from("X-Api-Key"), String::from("flag")),
+                (String::from("X-Team"), String::from("rescue")),
+            ]
+        );
+        assert!(super::endpoint(None, &["X-Api-Key"], None, &config).is_err());
+    }
+
+    #[test]
+    fn test_endpoint_elsewhere() {
+        let mut config = Config {
+            rpc: Some(String::from("http://config:8545")),
+            auth: Some(String::from("bearer config")),
+            ..Config::default()
+        };
+        config
+            .headers
+            .insert(String::from("X-Api-Key"), String::from("config"));
+
+        // the credentials of the config node never leak to another one
+        let endpoint = endpoint(Some("ws://flag:8546"), &[], None, &config).unwrap();
+
+        assert_eq!(endpoint.url, "ws://flag:8546");
+        assert!(endpoint.headers.is_empty());
+
+        let endpoint = super::endpoint(
+            Some("http://flag:8545"),
+            &["X-Api-Key: flag"],
+            Some("bearer flag"),
+            &config,
+        )
+        .unwrap();
+
+        assert_eq!(
+            endpoint.headers,
+            vec![
+                (String::from("Authorization"), String::from("Bearer flag")),
+                (String::from("X-Api-Key"), String::from("flag")),
+            ]
+        );
+    }
+}
@@ -1 +0,0 @@
-pub const ENS_MAINNET_ADDR: &str = "314159265dD8dbb310642f98f50C066173C1259b";
@@ -1,6 +1,8 @@
 pub mod abis;
-pub mod addresses;
 pub mod endpoints;
 
-pub const ARGENT_GENESIS_BLOCK: i32 = 7_173_577;
 pub const ENS_REVERSE_REGISTRAR_DOMAIN: &str = "addr.reverse";
+pub const SESSIONS_DIR: &str = ".argent/sessions";
+pub const CONFIG_FILE: &str = ".argent/config.json";
+pub const NETWORKS_FILE: &str = ".argent/networks.json";
+pub const RELAY_GAS_OVERHEAD: u64 = 50_000;
@@ -1,23 +1,11 @@
 use crate::constants;
-use lazy_static::lazy_static;
+use crate::network;
 use tiny_keccak::{Hasher, Keccak};
 
 use web3::contract::{Contract, Options};
 use web3::futures::Future;
 use web3::types::{Address, H256};
 
-struct EnsSetting {
-    mainnet_addr: Address,
-}
-
-lazy_static! {
-    static ref ENS_SETTING: EnsSetting = EnsSetting {
-        mainnet_addr: constants::addresses::ENS_MAINNET_ADDR
-            .parse()
-            .expect("don't parse ens.mainnet.addr")
-    };
-}
-
 #[derive(Debug)]
 struct Resolver<T: web3::Transport> {
     contract: Contract<T>,
@@ -72,14 +60,18 @@ pub struct ENS<'a, T: web3::Transport> {
 }
 
 impl<'a, T: web3::Transport> ENS<'a, T> {
-    pub fn new(web3: &'a web3::Web3<T>) -> Self {
-        let contract =
-            Contract::from_json(web3.eth(), ENS_SETTING.mainnet_addr, constants::abis::ENS)
-                .expect("fail contract::from_json(ENS.abi)");
-        ENS::<'a, T> {
+    pub fn new(web3: &'a web3::Web3<T>) -> Result<Self, String> {
+        let registry = match network::current().ens() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        let contract = Contract::from_json(web3.eth(), registry, constants::abis::ENS)
+            .expect("fail contract::from_json(ENS.abi)");
+        Ok(ENS::<'a, T> {
             web3: web3,
             contract: contract,
-        }
+        })
     }
 
     pub fn name(&self, address: Address) -> Result<String, String> {
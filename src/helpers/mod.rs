@@ -1,12 +1,25 @@
 use crate::ens::ENS;
+use crate::sign_hash;
+use chrono::NaiveDateTime;
 use std::str::FromStr;
+use std::thread;
+use std::time::{Duration, Instant};
 use web3::api::Web3;
 use web3::futures::Future;
-use web3::types::{Address, Bytes, H520};
+use web3::types::{
+    Address, BlockId, BlockNumber, Bytes, TransactionId, TransactionReceipt, H256, H520, U256,
+};
+
+const RECEIPT_POLL_INTERVAL: u64 = 5;
+const RECEIPT_TIMEOUT: u64 = 30 * 60;
+const REVERT_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
 
 pub fn to_address<T: web3::Transport>(address: &str, web3: &Web3<T>) -> Result<Address, String> {
     if address.ends_with(".eth") || address.ends_with(".xyz") {
-        let ens = ENS::new(&web3);
+        let ens = match ENS::new(&web3) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
         match ens.address(address) {
             Ok(s) => return Ok(s),
@@ -21,7 +34,10 @@ pub fn to_address<T: web3::Transport>(address: &str, web3: &Web3<T>) -> Result<A
 }
 
 pub fn to_ens<T: web3::Transport>(address: Address, web3: &Web3<T>) -> Result<String, String> {
-    let ens = ENS::new(&web3);
+    let ens = match ENS::new(&web3) {
+        Ok(s) => s,
+        Err(e) => return Err(e),
+    };
 
     match ens.name(address) {
         Ok(s) => Ok(s),
@@ -29,6 +45,20 @@ pub fn to_ens<T: web3::Transport>(address: Address, web3: &Web3<T>) -> Result<St
     }
 }
 
+pub fn accounts<T: web3::Transport>(web3: &Web3<T>) -> Result<Vec<Address>, String> {
+    match web3.eth().accounts().wait() {
+        Ok(s) => Ok(s),
+        Err(_e) => Err(String::from("unable to fetch accounts")),
+    }
+}
+
+pub fn chain_id<T: web3::Transport>(web3: &Web3<T>) -> Result<u64, String> {
+    match web3.eth().chain_id().wait() {
+        Ok(s) => Ok(s.as_u64()),
+        Err(_e) => Err(String::from("unable to fetch chain id")),
+    }
+}
+
 pub fn sign<T: web3::Transport>(
     account: Address,
     data: Bytes,
@@ -41,3 +71,182 @@ pub fn sign<T: web3::Transport>(
         Err(_e) => Err(format!("unable to sign with account {:?}", account)),
     }
 }
+
+pub fn timestamp<T: web3::Transport>(web3: &Web3<T>) -> Result<U256, String> {
+    let result = web3.eth().block(BlockId::Number(BlockNumber::Latest));
+
+    match result.wait() {
+        Ok(Some(s)) => Ok(s.timestamp),
+        _ => Err(String::from("unable to fetch last block")),
+    }
+}
+
+pub fn block_timestamp<T: web3::Transport>(block: u64, web3: &Web3<T>) -> Result<u64, String> {
+    let result = web3
+        .eth()
+        .block(BlockId::Number(BlockNumber::Number(block.into())));
+
+    match result.wait() {
+        Ok(Some(s)) => Ok(s.timestamp.as_u64()),
+        _ => Err(format!("unable to fetch block {}", block)),
+    }
+}
+
+pub fn sender<T: web3::Transport>(tx: H256, web3: &Web3<T>) -> Result<Address, String> {
+    let result = web3.eth().transaction(TransactionId::Hash(tx));
+
+    match result.wait() {
+        Ok(Some(s)) => Ok(s.from),
+        _ => Err(format!("unable to fetch transaction {:?}", tx)),
+    }
+}
+
+// waits for a transaction to be mined, but not forever: dropped or underpriced ones never are
+pub fn receipt<T: web3::Transport>(tx: H256, web3: &Web3<T>) -> Result<TransactionReceipt, String> {
+    let start = Instant::now();
+
+    loop {
+        match web3.eth().transaction_receipt(tx).wait() {
+            Ok(Some(s)) => return Ok(s),
+            Ok(None) if start.elapsed().as_secs() >= RECEIPT_TIMEOUT => {
+                return Err(format!(
+                    "no receipt for {:?} after {}: it may have been dropped or underpriced",
+                    tx,
+                    duration(RECEIPT_TIMEOUT)
+                ))
+            }
+            Ok(None) => thread::sleep(Duration::from_secs(RECEIPT_POLL_INTERVAL)),
+            Err(_e) => return Err(format!("unable to fetch receipt for {:?}", tx)),
+        }
+    }
+}
+
+pub fn duration(seconds: u64) -> String {
+    let days = seconds / 86400;
+    let hours = (seconds % 86400) / 3600;
+    let minutes = (seconds % 3600) / 60;
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, seconds % 60)
+    }
+}
+
+pub fn date(timestamp: u64) -> String {
+    NaiveDateTime::from_timestamp(timestamp as i64, 0)
+        .format("%Y-%m-%d %H:%M:%S UTC")
+        .to_string()
+}
+
+pub fn selector(function: &ethabi::Function) -> [u8; 4] {
+    let params: Vec<String> = function
+        .inputs
+        .iter()
+        .map(|param| param.kind.to_string())
+        .collect();
+    let signature = format!("{}({})", function.name, params.join(","));
+    let hash = sign_hash::keccak256(signature.as_bytes());
+
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&hash[..4]);
+
+    selector
+}
+
+// parses an amount of gwei, decimals included, into wei
+pub fn gwei(amount: &str) -> Result<U256, String> {
+    let error = format!("invalid gwei amount {}", amount);
+
+    let mut parts = amount.splitn(2, '.');
+    let units = parts.next().unwrap_or("");
+    let decimals = parts.next().unwrap_or("");
+
+    if (units.is_empty() && decimals.is_empty())
+        || decimals.len() > 9
+        || !units
+            .chars()
+            .chain(decimals.chars())
+            .all(|c| c.is_ascii_digit())
+    {
+        return Err(error);
+    }
+
+    let wei = format!("{}{:0<9}", units, decimals);
+
+    match U256::from_dec_str(&wei) {
+        Ok(s) => Ok(s),
+        Err(_e) => Err(error),
+    }
+}
+
+// reason of a reverted call, as reported by the node
+pub fn revert_reason(error: &web3::Error) -> String {
+    match error {
+        web3::Error::Rpc(e) => {
+            let data = e
+                .data
+                .as_ref()
+                .and_then(|data| data.as_str())
+                .and_then(|data| hex::decode(data.trim_start_matches("0x")).ok());
+
+            match data.and_then(|data| decode_revert(&data)) {
+                Some(s) => s,
+                None => e.message.clone(),
+            }
+        }
+        e => format!("{}", e),
+    }
+}
+
+// decodes the Error(string) payload of a revert
+pub fn decode_revert(data: &[u8]) -> Option<String> {
+    if data.len() < 4 || data[..4] != REVERT_SELECTOR {
+        return None;
+    }
+
+    match ethabi::decode(&[ethabi::ParamType::String], &data[4..]) {
+        Ok(tokens) => match tokens.into_iter().next() {
+            Some(ethabi::Token::String(s)) => Some(s),
+            _ => None,
+        },
+        Err(_e) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{decode_revert, gwei};
+    use web3::types::U256;
+
+    #[test]
+    fn test_gwei() {
+        assert_eq!(gwei("20").unwrap(), U256::from(20_000_000_000u64));
+        assert_eq!(gwei("1.5").unwrap(), U256::from(1_500_000_000u64));
+        assert_eq!(gwei("0.000000001").unwrap(), U256::from(1));
+        assert!(gwei("0.0000000001").is_err());
+        assert!(gwei("1.2.3").is_err());
+        assert!(gwei("").is_err());
+    }
+
+    #[test]
+    fn test_decode_revert() {
+        // Error("RM: Invalid signatures")
+        let data = hex::decode(
+            "08c379a0\
+             0000000000000000000000000000000000000000000000000000000000000020\
+             0000000000000000000000000000000000000000000000000000000000000016\
+             524d3a20496e76616c6964207369676e61747572657300000000000000000000",
+        )
+        .unwrap();
+
+        assert_eq!(
+            decode_revert(&data),
+            Some(String::from("RM: Invalid signatures"))
+        );
+        assert_eq!(decode_revert(&data[4..]), None);
+        assert_eq!(decode_revert(&[]), None);
+    }
+}
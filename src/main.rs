@@ -1,13 +1,22 @@
+mod bundle;
 mod cmd;
+mod config;
 mod constants;
 mod ens;
 mod helpers;
 mod modules;
+mod network;
+mod session;
+mod sign_hash;
+mod signer;
 mod token;
+mod transport;
 mod tui;
 mod wallet;
 
-use clap::{App, AppSettings, Arg};
+use clap::{App, AppSettings, Arg, ArgMatches};
+use dialoguer::PasswordInput;
+use std::io;
 use std::process;
 
 fn main() {
@@ -16,18 +25,129 @@ fn main() {
 
     tui::figlet();
 
-    let (_eloop, transport) = web3::transports::Http::new(constants::endpoints::FRAME)
-        .unwrap_or_else(|_e| {
-            tui::error("invalid ethereum endpoint".to_string());
-            process::exit(1)
-        });
-    let web3 = web3::Web3::new(transport);
-
     let matches = App::new("argent")
         .about("A CLI for the Argent wallet")
         .version("1.0")
         .author("Olivier Sarrouy <osarrouy@protonmail.com>")
         .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("rpc")
+                .help("URL of the ethereum node, over http, ws or the path of an IPC socket [default: $ARGENT_RPC, the config file or Frame]")
+                .long("rpc")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("rpc-header")
+                .help("Header sent to the ethereum node, as name: value")
+                .long("rpc-header")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("rpc-auth")
+                .help("Authentication to the ethereum node, as basic <user>:<password> or bearer <token>")
+                .long("rpc-auth")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("network")
+                .help("Network whose addresses to use, among mainnet, ropsten, goerli, devnet and the ones of ~/.argent/networks.json [default: detected from the chain id]. Only mainnet is supported out of the box: the Argent modules of ropsten, goerli and devnet are not bundled and must be listed in ~/.argent/networks.json")
+                .long("network")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("gas-price")
+                .help("Gas price of relayed and offline transactions, in gwei")
+                .long("gas-price")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("gas-limit")
+                .help("Gas limit signed into relayed transactions [default: estimated]")
+                .long("gas-limit")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("refund")
+                .help("Makes the wallet refund the gas of relayed transactions")
+                .long("refund")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("from")
+                .help("Account signing for the wallet [default: the local key, or picked]")
+                .long("from")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("relayer")
+                .help("Account sending relayed transactions [default: the signing account]")
+                .long("relayer")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("keystore")
+                .help("Signs with the key of a JSON keystore instead of the node accounts")
+                .long("keystore")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("mnemonic")
+                .help("Signs with a key derived from a mnemonic, read from stdin or prompted")
+                .long("mnemonic")
+                .conflicts_with("keystore")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("hd-path")
+                .help("Derivation path of the mnemonic key [default: m/44'/60'/0'/0/0]")
+                .long("hd-path")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("offline")
+                .help("Prints the transactions signed by the local key instead of sending them. Needs --network or --chain-id, so that the chain id is not read from the node. Direct transactions are then signed without any RPC call, but relayed ones still read their relayer nonce, gas limit, owner and guardians from the node")
+                .long("offline")
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("tx-nonce")
+                .help("Nonce of the local key for offline transactions")
+                .long("tx-nonce")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("tx-gas-limit")
+                .help("Gas limit of offline transactions")
+                .long("tx-gas-limit")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("chain-id")
+                .help("Chain id signed into offline transactions and exported bundles [default: the one of the network]")
+                .long("chain-id")
+                .takes_value(true)
+                .global(true),
+        )
+        .arg(
+            Arg::with_name("dry-run")
+                .help("Simulates state-changing commands without broadcasting them")
+                .long("dry-run")
+                .global(true),
+        )
         .subcommand(
             App::new("ens")
                 .about("Prints the address and ENS name of a wallet")
@@ -39,28 +159,120 @@ fn main() {
                 ),
         )
         .subcommand(
-            App::new("owner").about("Prints the owner of a wallet").arg(
-                Arg::with_name(WALLET_ARG_NAME)
-                    .help(WALLET_ARG_HELP)
-                    .index(1)
-                    .required(true),
-            ),
+            App::new("owner")
+                .about("Prints the owner of a wallet")
+                .setting(AppSettings::SubcommandsNegateReqs)
+                .arg(
+                    Arg::with_name(WALLET_ARG_NAME)
+                        .help(WALLET_ARG_HELP)
+                        .index(1)
+                        .required(true),
+                )
+                .subcommand(
+                    App::new("transfer")
+                        .about("Ownership transfer related commands")
+                        .setting(AppSettings::SubcommandRequiredElseHelp)
+                        .subcommand(
+                            App::new("init")
+                                .aliases(&["initialize", "execute"])
+                                .about("Initialize the ownership transfer of a wallet")
+                                .arg(
+                                    Arg::with_name(WALLET_ARG_NAME)
+                                        .help(WALLET_ARG_HELP)
+                                        .index(1)
+                                        .required(true),
+                                )
+                                .arg(
+                                    Arg::with_name("owner")
+                                        .help("New owner of the wallet")
+                                        .index(2)
+                                        .required(true),
+                                )
+                                .arg(
+                                    Arg::with_name("reset")
+                                        .help("Discards the signatures collected so far")
+                                        .long("reset"),
+                                ),
+                        )
+                        .subcommand(
+                            App::new("finalize")
+                                .about("Finalize the ownership transfer of a wallet")
+                                .arg(
+                                    Arg::with_name(WALLET_ARG_NAME)
+                                        .help(WALLET_ARG_HELP)
+                                        .index(1)
+                                        .required(true),
+                                ),
+                        )
+                        .subcommand(
+                            App::new("cancel")
+                                .about("Cancel the ownership transfer of a wallet")
+                                .arg(
+                                    Arg::with_name(WALLET_ARG_NAME)
+                                        .help(WALLET_ARG_HELP)
+                                        .index(1)
+                                        .required(true),
+                                )
+                                .arg(
+                                    Arg::with_name("reset")
+                                        .help("Discards the signatures collected so far")
+                                        .long("reset"),
+                                ),
+                        ),
+                ),
         )
         .subcommand(
-            App::new("lock").about("Lock a wallet").arg(
-                Arg::with_name(WALLET_ARG_NAME)
-                    .help(WALLET_ARG_HELP)
-                    .index(1)
-                    .required(true),
-            ),
+            App::new("lock")
+                .about("Lock a wallet")
+                .setting(AppSettings::SubcommandsNegateReqs)
+                .arg(
+                    Arg::with_name(WALLET_ARG_NAME)
+                        .help(WALLET_ARG_HELP)
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("guardian")
+                        .help("Relays the lock with the signature of this guardian")
+                        .long("guardian")
+                        .takes_value(true),
+                )
+                .subcommand(
+                    App::new("status")
+                        .about("Prints the lock status of a wallet")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("history")
+                        .about("Prints the lock and unlock history of a wallet")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        ),
+                ),
         )
         .subcommand(
-            App::new("unlock").about("Unlock a wallet").arg(
-                Arg::with_name(WALLET_ARG_NAME)
-                    .help(WALLET_ARG_HELP)
-                    .index(1)
-                    .required(true),
-            ),
+            App::new("unlock")
+                .about("Unlock a wallet")
+                .arg(
+                    Arg::with_name(WALLET_ARG_NAME)
+                        .help(WALLET_ARG_HELP)
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("guardian")
+                        .help("Relays the unlock with the signature of this guardian")
+                        .long("guardian")
+                        .takes_value(true),
+                ),
         )
         .subcommand(
             App::new("balance")
@@ -93,6 +305,80 @@ fn main() {
                                 .index(1)
                                 .required(true),
                         ),
+                )
+                .subcommand(
+                    App::new("pending")
+                        .about("Prints the pending guardian changes of a wallet")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("add")
+                        .about("Request the addition of a guardian to a wallet")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("guardian")
+                                .help("Address or ENS name of the guardian")
+                                .index(2)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("revoke")
+                        .about("Request the revocation of a guardian of a wallet")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("guardian")
+                                .help("Address or ENS name of the guardian")
+                                .index(2)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("confirm")
+                        .about("Confirm the pending addition or revocation of a guardian")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("guardian")
+                                .help("Address or ENS name of the guardian")
+                                .index(2)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("cancel")
+                        .about("Cancel the pending addition or revocation of a guardian")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("guardian")
+                                .help("Address or ENS name of the guardian")
+                                .index(2)
+                                .required(true),
+                        ),
                 ),
         )
         .subcommand(
@@ -129,6 +415,17 @@ fn main() {
                                 .help("New owner of the wallet")
                                 .index(2)
                                 .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("reset")
+                                .help("Discards the signatures collected so far")
+                                .long("reset"),
+                        )
+                        .arg(
+                            Arg::with_name("export")
+                                .help("Writes the signed transaction to a relay bundle instead of submitting it")
+                                .long("export")
+                                .takes_value(true),
                         ),
                 )
                 .subcommand(
@@ -144,6 +441,31 @@ fn main() {
                 .subcommand(
                     App::new("cancel")
                         .about("Cancel the recovery of a wallet")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("reset")
+                                .help("Discards the signatures collected so far")
+                                .long("reset"),
+                        ),
+                )
+                .subcommand(
+                    App::new("status")
+                        .about("Prints the recovery status of a wallet")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("history")
+                        .about("Prints the recovery and ownership transfer history of a wallet")
                         .arg(
                             Arg::with_name(WALLET_ARG_NAME)
                                 .help(WALLET_ARG_HELP)
@@ -152,15 +474,161 @@ fn main() {
                         ),
                 ),
         )
+        .subcommand(
+            App::new("mnemonic")
+                .about("Lists the accounts derived from a mnemonic and their role in a wallet")
+                .arg(
+                    Arg::with_name(WALLET_ARG_NAME)
+                        .help(WALLET_ARG_HELP)
+                        .index(1)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("count")
+                        .help("Number of accounts to derive from the derivation path")
+                        .long("count")
+                        .takes_value(true)
+                        .default_value("10"),
+                ),
+        )
+        .subcommand(
+            App::new("relay")
+                .about("Relayer related commands")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    App::new("nonce")
+                        .about("Prints the next relayer nonce of a wallet for a module")
+                        .arg(
+                            Arg::with_name(WALLET_ARG_NAME)
+                                .help(WALLET_ARG_HELP)
+                                .index(1)
+                                .required(true),
+                        )
+                        .arg(
+                            Arg::with_name("module")
+                                .help("Address or name of the module")
+                                .long("module")
+                                .takes_value(true)
+                                .required(true),
+                        ),
+                )
+                .subcommand(
+                    App::new("submit")
+                        .about("Submits a relay bundle signed elsewhere")
+                        .arg(
+                            Arg::with_name("bundle")
+                                .help("Path of the relay bundle")
+                                .index(1)
+                                .required(true),
+                        ),
+                ),
+        )
+        .subcommand(
+            App::new("verify-sig")
+                .about("Recovers the signers of a relayed transaction and checks their order, --gas-price and --gas-limit being the signed ones")
+                .arg(
+                    Arg::with_name(WALLET_ARG_NAME)
+                        .help(WALLET_ARG_HELP)
+                        .long("wallet")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("module")
+                        .help("Address or name of the module")
+                        .long("module")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("data")
+                        .help("Relayed data, hex encoded")
+                        .long("data")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("nonce")
+                        .help("Relayer nonce signed into the transaction")
+                        .long("nonce")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("sig")
+                        .help("Concatenated signatures, hex encoded")
+                        .long("sig")
+                        .takes_value(true)
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            App::new("tx")
+                .about("Raw transactions related commands")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    App::new("broadcast")
+                        .about("Broadcasts a transaction signed offline")
+                        .arg(
+                            Arg::with_name("raw")
+                                .help("Raw transaction, hex encoded")
+                                .index(1)
+                                .required(true),
+                        ),
+                ),
+        )
         .get_matches();
 
+    let (_eloop, transport) = transport::Transport::new(&endpoint(&matches)).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1)
+    });
+    let web3 = web3::Web3::new(transport);
+
+    network::select(network(&matches, &web3));
+
     match matches.subcommand() {
         ("ens", Some(args)) => {
             cmd::generics::ens(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
         }
-        ("owner", Some(args)) => {
-            cmd::generics::owner(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
-        }
+        ("owner", Some(params)) => match params.subcommand() {
+            ("transfer", Some(params)) => match params.subcommand() {
+                ("init", Some(args)) => {
+                    cmd::owner::transfer(
+                        args.value_of(WALLET_ARG_NAME).unwrap(),
+                        args.value_of("owner").unwrap(),
+                        args.is_present("reset"),
+                        accounts(args, &web3),
+                        gas(args),
+                        args.is_present("dry-run"),
+                        web3,
+                    );
+                }
+                ("finalize", Some(args)) => {
+                    cmd::owner::finalize(
+                        args.value_of(WALLET_ARG_NAME).unwrap(),
+                        accounts(args, &web3),
+                        gas(args),
+                        args.is_present("dry-run"),
+                        web3,
+                    );
+                }
+                ("cancel", Some(args)) => {
+                    cmd::owner::cancel(
+                        args.value_of(WALLET_ARG_NAME).unwrap(),
+                        args.is_present("reset"),
+                        accounts(args, &web3),
+                        gas(args),
+                        args.is_present("dry-run"),
+                        web3,
+                    );
+                }
+                _ => unreachable!(),
+            },
+            _ => {
+                cmd::generics::owner(params.value_of(WALLET_ARG_NAME).unwrap(), web3);
+            }
+        },
         ("balance", Some(args)) => {
             cmd::generics::balance(
                 args.value_of(WALLET_ARG_NAME).unwrap(),
@@ -168,11 +636,33 @@ fn main() {
                 web3,
             );
         }
-        ("lock", Some(args)) => {
-            cmd::generics::lock(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
-        }
+        ("lock", Some(params)) => match params.subcommand() {
+            ("status", Some(args)) => {
+                cmd::lock::status(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+            }
+            ("history", Some(args)) => {
+                cmd::lock::history(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+            }
+            _ => {
+                cmd::generics::lock(
+                    params.value_of(WALLET_ARG_NAME).unwrap(),
+                    params.value_of("guardian"),
+                    accounts(params, &web3),
+                    gas(params),
+                    params.is_present("dry-run"),
+                    web3,
+                );
+            }
+        },
         ("unlock", Some(args)) => {
-            cmd::generics::unlock(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+            cmd::generics::unlock(
+                args.value_of(WALLET_ARG_NAME).unwrap(),
+                args.value_of("guardian"),
+                accounts(args, &web3),
+                gas(args),
+                args.is_present("dry-run"),
+                web3,
+            );
         }
         ("modules", Some(params)) => match params.subcommand() {
             ("ls", Some(args)) => {
@@ -184,6 +674,49 @@ fn main() {
             ("ls", Some(args)) => {
                 cmd::guardians::ls(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
             }
+            ("pending", Some(args)) => {
+                cmd::guardians::pending(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+            }
+            ("add", Some(args)) => {
+                cmd::guardians::add(
+                    args.value_of(WALLET_ARG_NAME).unwrap(),
+                    args.value_of("guardian").unwrap(),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
+                    web3,
+                );
+            }
+            ("revoke", Some(args)) => {
+                cmd::guardians::revoke(
+                    args.value_of(WALLET_ARG_NAME).unwrap(),
+                    args.value_of("guardian").unwrap(),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
+                    web3,
+                );
+            }
+            ("confirm", Some(args)) => {
+                cmd::guardians::confirm(
+                    args.value_of(WALLET_ARG_NAME).unwrap(),
+                    args.value_of("guardian").unwrap(),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
+                    web3,
+                );
+            }
+            ("cancel", Some(args)) => {
+                cmd::guardians::cancel(
+                    args.value_of(WALLET_ARG_NAME).unwrap(),
+                    args.value_of("guardian").unwrap(),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
+                    web3,
+                );
+            }
             _ => unreachable!(),
         },
         ("recovery", Some(params)) => match params.subcommand() {
@@ -191,14 +724,92 @@ fn main() {
                 cmd::recovery::init(
                     args.value_of(WALLET_ARG_NAME).unwrap(),
                     args.value_of("owner").unwrap(),
+                    args.is_present("reset"),
+                    args.value_of("export"),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
                     web3,
                 );
             }
             ("cancel", Some(args)) => {
-                cmd::recovery::cancel(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+                cmd::recovery::cancel(
+                    args.value_of(WALLET_ARG_NAME).unwrap(),
+                    args.is_present("reset"),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
+                    web3,
+                );
             }
             ("finalize", Some(args)) => {
-                cmd::recovery::finalize(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+                cmd::recovery::finalize(
+                    args.value_of(WALLET_ARG_NAME).unwrap(),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
+                    web3,
+                );
+            }
+            ("status", Some(args)) => {
+                cmd::recovery::status(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+            }
+            ("history", Some(args)) => {
+                cmd::recovery::history(args.value_of(WALLET_ARG_NAME).unwrap(), web3);
+            }
+            _ => unreachable!(),
+        },
+        ("mnemonic", Some(args)) => {
+            let count = args
+                .value_of("count")
+                .unwrap()
+                .parse()
+                .unwrap_or_else(|_e| {
+                    tui::error(String::from("invalid count"));
+                    process::exit(1);
+                });
+
+            cmd::mnemonic::ls(
+                args.value_of(WALLET_ARG_NAME).unwrap(),
+                &seed(),
+                hd_path(args),
+                count,
+                web3,
+            );
+        }
+        ("relay", Some(params)) => match params.subcommand() {
+            ("nonce", Some(args)) => {
+                cmd::relay::nonce(
+                    args.value_of(WALLET_ARG_NAME).unwrap(),
+                    args.value_of("module").unwrap(),
+                    web3,
+                );
+            }
+            ("submit", Some(args)) => {
+                cmd::relay::submit(
+                    args.value_of("bundle").unwrap(),
+                    accounts(args, &web3),
+                    gas(args),
+                    args.is_present("dry-run"),
+                    web3,
+                );
+            }
+            _ => unreachable!(),
+        },
+        ("verify-sig", Some(args)) => {
+            cmd::signature::verify(
+                args.value_of(WALLET_ARG_NAME).unwrap(),
+                args.value_of("module").unwrap(),
+                args.value_of("data").unwrap(),
+                args.value_of("nonce").unwrap(),
+                args.value_of("sig").unwrap(),
+                fees(args),
+                web3,
+            );
+        }
+        ("tx", Some(params)) => match params.subcommand() {
+            ("broadcast", Some(args)) => {
+                cmd::tx::broadcast(args.value_of("raw").unwrap(), web3);
             }
             _ => unreachable!(),
         },
@@ -207,3 +818,241 @@ fn main() {
         _ => unreachable!(),
     }
 }
+
+// global args land in the matches of the subcommand they follow
+fn leaf<'a>(matches: &'a ArgMatches<'a>) -> &'a ArgMatches<'a> {
+    let mut args = matches;
+    while let (_, Some(sub)) = args.subcommand() {
+        args = sub;
+    }
+
+    args
+}
+
+fn endpoint(matches: &ArgMatches) -> config::Endpoint {
+    let args = leaf(matches);
+
+    let config = config::Config::load().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let headers: Vec<&str> = args
+        .values_of("rpc-header")
+        .map(|headers| headers.collect())
+        .unwrap_or_default();
+
+    config::endpoint(
+        args.value_of("rpc"),
+        &headers,
+        args.value_of("rpc-auth"),
+        &config,
+    )
+    .unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    })
+}
+
+// --network, or the network of --chain-id or else of the chain id of the node, which is never
+// asked for offline. --chain-id overrides the one of the network
+fn network<T: web3::Transport>(matches: &ArgMatches, web3: &web3::Web3<T>) -> network::Network {
+    let args = leaf(matches);
+    let name = args.value_of("network");
+
+    let chain_id = args.value_of("chain-id").map(|chain_id| {
+        chain_id.parse::<u64>().unwrap_or_else(|_e| {
+            tui::error(format!("invalid --chain-id {}", chain_id));
+            process::exit(1);
+        })
+    });
+
+    if name.is_none() && chain_id.is_none() && args.is_present("offline") {
+        tui::error(String::from(
+            "signing offline needs --network or --chain-id",
+        ));
+        process::exit(1);
+    }
+
+    let networks = network::load().unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    let detected = match (name, chain_id) {
+        (Some(_), _) => 0,
+        (None, Some(s)) => s,
+        (None, None) => helpers::chain_id(web3).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        }),
+    };
+
+    let mut network = network::find(&networks, name, detected).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    if let Some(chain_id) = chain_id {
+        network.chain_id = chain_id;
+    }
+
+    network
+}
+
+fn gas(args: &ArgMatches) -> modules::Gas {
+    let price = args.value_of("gas-price").map(|price| {
+        helpers::gwei(price).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        })
+    });
+
+    let limit = args.value_of("gas-limit").map(|limit| {
+        web3::types::U256::from_dec_str(limit).unwrap_or_else(|_e| {
+            tui::error(format!("invalid gas limit {}", limit));
+            process::exit(1);
+        })
+    });
+
+    modules::Gas {
+        price,
+        limit,
+        refund: args.is_present("refund"),
+    }
+}
+
+// fees signed into a relayed transaction, which are never estimated as they are part of its hash
+fn fees(args: &ArgMatches) -> modules::Fees {
+    match gas(args) {
+        modules::Gas {
+            price: Some(gas_price),
+            limit: Some(gas_limit),
+            ..
+        } => modules::Fees {
+            gas_price,
+            gas_limit,
+        },
+        _ => {
+            tui::error(String::from(
+                "--gas-price and --gas-limit are required: they are signed into the hash",
+            ));
+            process::exit(1);
+        }
+    }
+}
+
+fn accounts<T: web3::Transport>(args: &ArgMatches, web3: &web3::Web3<T>) -> signer::Accounts {
+    let address = |name: &str| {
+        args.value_of(name).map(|value| {
+            helpers::to_address(value, web3).unwrap_or_else(|e| {
+                tui::error(e);
+                process::exit(1);
+            })
+        })
+    };
+
+    let key = key(args);
+    let offline = offline(args);
+
+    if offline.is_some() && key.is_none() {
+        tui::error(String::from(
+            "signing offline needs a keystore or a mnemonic",
+        ));
+        process::exit(1);
+    }
+
+    signer::Accounts {
+        key,
+        from: address("from"),
+        relayer: address("relayer"),
+        offline,
+    }
+}
+
+fn offline(args: &ArgMatches) -> Option<signer::Offline> {
+    if !args.is_present("offline") {
+        return None;
+    }
+
+    let number = |name: &str| match args.value_of(name) {
+        Some(value) => web3::types::U256::from_dec_str(value).unwrap_or_else(|_e| {
+            tui::error(format!("invalid --{} {}", name, value));
+            process::exit(1);
+        }),
+        None => {
+            tui::error(format!("signing offline needs --{}", name));
+            process::exit(1);
+        }
+    };
+
+    let gas_price = match args.value_of("gas-price") {
+        Some(price) => helpers::gwei(price).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        }),
+        None => {
+            tui::error(String::from("signing offline needs --gas-price"));
+            process::exit(1);
+        }
+    };
+
+    Some(signer::Offline {
+        chain_id: network::current().chain_id,
+        nonce: number("tx-nonce"),
+        gas_price,
+        gas: number("tx-gas-limit"),
+    })
+}
+
+fn key(args: &ArgMatches) -> Option<signer::Key> {
+    if args.is_present("mnemonic") {
+        let key = signer::mnemonic::derive(&seed(), hd_path(args)).unwrap_or_else(|e| {
+            tui::error(e);
+            process::exit(1);
+        });
+
+        return Some(key);
+    }
+
+    let path = args.value_of("keystore")?;
+
+    let password = PasswordInput::new()
+        .with_prompt(&format!("-[ password of {}", path))
+        .interact()
+        .unwrap();
+
+    let key = signer::keystore::load(path, &password).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    });
+
+    Some(key)
+}
+
+// the mnemonic is read from stdin when piped, prompted otherwise
+fn seed() -> Vec<u8> {
+    let phrase = if atty::is(atty::Stream::Stdin) {
+        PasswordInput::new()
+            .with_prompt("-[ mnemonic")
+            .interact()
+            .unwrap()
+    } else {
+        let mut phrase = String::new();
+        io::stdin().read_line(&mut phrase).unwrap_or_else(|_e| {
+            tui::error(String::from("unable to read mnemonic from stdin"));
+            process::exit(1);
+        });
+        phrase
+    };
+
+    signer::mnemonic::seed(&phrase).unwrap_or_else(|e| {
+        tui::error(e);
+        process::exit(1);
+    })
+}
+
+fn hd_path<'a>(args: &'a ArgMatches) -> &'a str {
+    args.value_of("hd-path")
+        .unwrap_or(signer::mnemonic::DEFAULT_PATH)
+}
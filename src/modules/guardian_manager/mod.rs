@@ -0,0 +1,250 @@
+use crate::constants;
+use crate::helpers;
+use crate::modules::{Gas, Outcome, RelayedModule, Relayer};
+use crate::network;
+use crate::session::Session;
+use crate::signer::Signer;
+use crate::wallet::Wallet;
+use web3::api::Web3;
+use web3::contract::Contract;
+use web3::contract::Options;
+use web3::futures::Future;
+use web3::types::{Address, FilterBuilder, H256, U256};
+
+#[derive(Clone, Debug)]
+pub struct PendingChange {
+    pub guardian: Address,
+    pub addition: bool,
+    pub execute_after: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct GuardianManager<'a, T: web3::Transport> {
+    pub address: Address,
+    abi: ethabi::Contract,
+    contract: web3::contract::Contract<T>,
+    module: RelayedModule<'a, T>,
+    web3: &'a Web3<T>,
+}
+
+impl<'a, T: web3::Transport> GuardianManager<'a, T> {
+    pub fn new(web3: &'a Web3<T>) -> Result<Self, String> {
+        let address = match network::current().module("GuardianManager") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        Ok(GuardianManager::<'a, T> {
+            address,
+            abi: ethabi::Contract::load(constants::abis::GUARDIAN_MANAGER).unwrap(),
+            contract: Contract::from_json(web3.eth(), address, constants::abis::GUARDIAN_MANAGER)
+                .unwrap(),
+            module: RelayedModule::new(address, constants::abis::GUARDIAN_MANAGER, web3).unwrap(),
+            web3,
+        })
+    }
+
+    pub fn with_gas(mut self, gas: Gas) -> Self {
+        self.module = self.module.with_gas(gas);
+        self
+    }
+
+    pub fn with_relayer(mut self, relayer: &'a dyn Signer) -> Self {
+        self.module = self.module.with_relayer(relayer);
+        self
+    }
+
+    pub fn add_guardian(&self, wallet: Address, guardian: Address) -> Result<Session, String> {
+        let data = self.encode("addGuardian", wallet, guardian);
+
+        self.module.prepare(wallet, data)
+    }
+
+    pub fn revoke_guardian(&self, wallet: Address, guardian: Address) -> Result<Session, String> {
+        let data = self.encode("revokeGuardian", wallet, guardian);
+
+        self.module.prepare(wallet, data)
+    }
+
+    pub fn confirm(&self, wallet: Address, guardian: Address) -> Result<Session, String> {
+        let change = match self.pending_change(wallet, guardian) {
+            Ok(Some(s)) => s,
+            Ok(None) => return Err(format!("no pending change for guardian {:?}", guardian)),
+            Err(e) => return Err(e),
+        };
+
+        let window = match self.security_window() {
+            Ok(s) => s.as_u64(),
+            Err(e) => return Err(e),
+        };
+
+        let now = match helpers::timestamp(self.web3) {
+            Ok(s) => s.as_u64(),
+            Err(e) => return Err(e),
+        };
+
+        if now <= change.execute_after {
+            return Err(format!(
+                "confirmation window opens on {} [{} left]",
+                helpers::date(change.execute_after),
+                helpers::duration(change.execute_after - now)
+            ));
+        }
+
+        if now >= change.execute_after + window {
+            return Err(format!(
+                "confirmation window closed on {}",
+                helpers::date(change.execute_after + window)
+            ));
+        }
+
+        let data = if change.addition {
+            self.encode("confirmGuardianAddition", wallet, guardian)
+        } else {
+            self.encode("confirmGuardianRevokation", wallet, guardian)
+        };
+
+        // confirmations do not require any signature: anyone can relay them
+        self.module.prepare(wallet, data)
+    }
+
+    pub fn cancel(&self, wallet: Address, guardian: Address) -> Result<Session, String> {
+        let change = match self.pending_change(wallet, guardian) {
+            Ok(Some(s)) => s,
+            Ok(None) => return Err(format!("no pending change for guardian {:?}", guardian)),
+            Err(e) => return Err(e),
+        };
+
+        let data = if change.addition {
+            self.encode("cancelGuardianAddition", wallet, guardian)
+        } else {
+            self.encode("cancelGuardianRevokation", wallet, guardian)
+        };
+
+        self.module.prepare(wallet, data)
+    }
+
+    pub fn pending_change(
+        &self,
+        wallet: Address,
+        guardian: Address,
+    ) -> Result<Option<PendingChange>, String> {
+        match self.pending_changes(wallet) {
+            Ok(s) => Ok(s.into_iter().find(|change| change.guardian == guardian)),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn pending_changes(&self, wallet: Address) -> Result<Vec<PendingChange>, String> {
+        let events: Vec<&ethabi::Event> = [
+            "GuardianAdditionRequested",
+            "GuardianRevokationRequested",
+            "GuardianAdditionCancelled",
+            "GuardianRevokationCancelled",
+            "GuardianAdded",
+            "GuardianRevoked",
+        ]
+        .iter()
+        .map(|name| self.abi.event(name).unwrap())
+        .collect();
+
+        let filter = FilterBuilder::default()
+            .address(vec![self.address])
+            .topics(
+                Some(events.iter().map(|event| event.signature()).collect()),
+                Some(vec![H256::from(wallet)]),
+                None,
+                None,
+            )
+            .from_block(network::current().start_block.into())
+            .build();
+
+        let logs = match self.web3.eth().logs(filter).wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to fetch guardians logs for {:?}", wallet)),
+        };
+
+        let mut pending = Vec::<PendingChange>::new();
+
+        for log in logs.iter() {
+            let guardian = Address::from(log.topics[2]);
+            let addition = log.topics[0] == events[0].signature();
+            let revokation = log.topics[0] == events[1].signature();
+
+            pending.retain(|change| change.guardian != guardian);
+
+            if addition || revokation {
+                let mut execute_after = [0u8; 32];
+                execute_after.copy_from_slice(&log.data.0[..32]);
+
+                pending.push(PendingChange {
+                    guardian,
+                    addition,
+                    execute_after: U256::from(execute_after).as_u64(),
+                });
+            }
+        }
+
+        Ok(pending)
+    }
+
+    pub fn security_period(&self) -> Result<U256, String> {
+        let result = self
+            .contract
+            .query("securityPeriod", (), None, Options::default(), None);
+
+        match result.wait() {
+            Ok(s) => Ok(s),
+            Err(_e) => Err(String::from("unable to fetch security period")),
+        }
+    }
+
+    pub fn security_window(&self) -> Result<U256, String> {
+        let result = self
+            .contract
+            .query("securityWindow", (), None, Options::default(), None);
+
+        match result.wait() {
+            Ok(s) => Ok(s),
+            Err(_e) => Err(String::from("unable to fetch security window")),
+        }
+    }
+
+    // requests and cancellations have to be signed by the owner of the wallet
+    pub fn sign(&self, session: &mut Session, signer: &dyn Signer) -> Result<(), String> {
+        let owner = match Wallet::new(session.wallet, self.web3).owner() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        if signer.address() != owner {
+            return Err(format!(
+                "{:?} is not the owner of {:?}",
+                signer.address(),
+                session.wallet
+            ));
+        }
+
+        self.module.sign(session, signer)
+    }
+
+    pub fn simulate(&self, session: &Session) -> Result<Outcome, String> {
+        self.module.simulate(session, Address::zero())
+    }
+
+    pub fn submit(&self, session: &Session) -> Result<H256, String> {
+        self.module.submit(session, Address::zero())
+    }
+
+    fn encode(&self, method: &str, wallet: Address, guardian: Address) -> ethabi::Bytes {
+        self.module
+            .encode(
+                method,
+                &[
+                    ethabi::Token::Address(wallet),
+                    ethabi::Token::Address(guardian),
+                ],
+            )
+            .unwrap()
+    }
+}
@@ -0,0 +1,230 @@
+use crate::constants;
+use crate::helpers;
+use crate::modules::{Gas, Outcome, RecoveryManager, RelayedModule, Relayer};
+use crate::network;
+use crate::session::Session;
+use crate::signer::Signer;
+use crate::wallet::Wallet;
+use web3::api::Web3;
+use web3::futures::Future;
+use web3::types::{Address, FilterBuilder, H256, U256};
+
+#[derive(Clone, Debug)]
+pub struct LockEvent {
+    pub locked: bool,
+    pub module: Address,
+    pub release_after: Option<u64>,
+    pub estimated: bool,
+    pub block: u64,
+    pub log_index: u64,
+    pub timestamp: u64,
+    pub tx: H256,
+    pub sender: Address,
+}
+
+#[derive(Clone, Debug)]
+pub struct LockManager<'a, T: web3::Transport> {
+    pub address: Address,
+    abi: ethabi::Contract,
+    module: RelayedModule<'a, T>,
+    web3: &'a Web3<T>,
+}
+
+impl<'a, T: web3::Transport> LockManager<'a, T> {
+    pub fn new(web3: &'a Web3<T>) -> Result<Self, String> {
+        let address = match network::current().module("LockManager") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        Ok(LockManager::<'a, T> {
+            address,
+            abi: ethabi::Contract::load(constants::abis::LOCK_MANAGER).unwrap(),
+            module: RelayedModule::new(address, constants::abis::LOCK_MANAGER, web3).unwrap(),
+            web3,
+        })
+    }
+
+    pub fn with_gas(mut self, gas: Gas) -> Self {
+        self.module = self.module.with_gas(gas);
+        self
+    }
+
+    pub fn with_relayer(mut self, relayer: &'a dyn Signer) -> Self {
+        self.module = self.module.with_relayer(relayer);
+        self
+    }
+
+    pub fn lock(&self, wallet: Address) -> Result<Session, String> {
+        let data = self.encode("lock", wallet);
+
+        self.module.prepare(wallet, data)
+    }
+
+    pub fn unlock(&self, wallet: Address) -> Result<Session, String> {
+        let data = self.encode("unlock", wallet);
+
+        self.module.prepare(wallet, data)
+    }
+
+    // locks set by the LockManager itself as well as the ones set by the RecoveryManager for the
+    // duration of a recovery, in the order they were logged
+    pub fn history(&self, wallet: Address) -> Result<Vec<LockEvent>, String> {
+        let locked = self.abi.event("Locked").unwrap();
+        let unlocked = self.abi.event("Unlocked").unwrap();
+
+        let filter = FilterBuilder::default()
+            .address(vec![self.address])
+            .topics(
+                Some(vec![locked.signature(), unlocked.signature()]),
+                Some(vec![H256::from(wallet)]),
+                None,
+                None,
+            )
+            .from_block(network::current().start_block.into())
+            .build();
+
+        let logs = match self.web3.eth().logs(filter).wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to fetch lock logs for {:?}", wallet)),
+        };
+
+        let mut events = Vec::<LockEvent>::new();
+
+        for log in logs.iter() {
+            let block = log.block_number.unwrap_or_default().as_u64();
+            let tx = log.transaction_hash.unwrap_or_default();
+
+            let timestamp = match helpers::block_timestamp(block, self.web3) {
+                Ok(s) => s,
+                Err(e) => return Err(e),
+            };
+
+            let sender = match helpers::sender(tx, self.web3) {
+                Ok(s) => s,
+                Err(e) => return Err(e),
+            };
+
+            let release_after = if log.topics[0] == locked.signature() {
+                let mut release_after = [0u8; 32];
+                release_after.copy_from_slice(&log.data.0[..32]);
+
+                Some(U256::from(release_after).as_u64())
+            } else {
+                None
+            };
+
+            events.push(LockEvent {
+                locked: release_after.is_some(),
+                module: self.address,
+                release_after,
+                estimated: false,
+                block,
+                log_index: log.log_index.unwrap_or_default().as_u64(),
+                timestamp,
+                tx,
+                sender,
+            });
+        }
+
+        let recoveries = match self.recovery_history(wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        events.extend(recoveries);
+        events.sort_by_key(|event| (event.block, event.log_index));
+
+        Ok(events)
+    }
+
+    // a single guardian signature is enough: the transaction itself is then sent by the
+    // relaying account, which pays for the gas
+    pub fn sign(&self, session: &mut Session, guardian: &dyn Signer) -> Result<(), String> {
+        let guardians = match Wallet::new(session.wallet, self.web3).guardians() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        if !guardians.contains(&guardian.address()) {
+            return Err(format!(
+                "{:?} is not a guardian of {:?}",
+                guardian.address(),
+                session.wallet
+            ));
+        }
+
+        self.module.sign(session, guardian)
+    }
+
+    pub fn simulate(&self, session: &Session) -> Result<Outcome, String> {
+        self.module.simulate(session, Address::zero())
+    }
+
+    pub fn submit(&self, session: &Session) -> Result<H256, String> {
+        self.module.submit(session, Address::zero())
+    }
+
+    // locks of recoveries, none when the network has no RecoveryManager
+    fn recovery_history(&self, wallet: Address) -> Result<Vec<LockEvent>, String> {
+        let recovery_manager = match RecoveryManager::new(self.web3) {
+            Ok(s) => s,
+            Err(_e) => return Ok(Vec::new()),
+        };
+
+        let recoveries = match recovery_manager.history(wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        let mut events = Vec::<LockEvent>::new();
+        let mut current = None;
+
+        for recovery in recoveries.into_iter() {
+            let locked = match recovery.name.as_str() {
+                "RecoveryExecuted" => true,
+                "RecoveryFinalized" | "RecoveryCanceled" => false,
+                _ => continue,
+            };
+
+            // recoveries do not log when their lock ends: it is derived from the lock period in
+            // force at their block, or estimated from the current one when the node has no
+            // archive of it. Without either, the end of the lock is unknown
+            let mut estimated = false;
+            let lock_period = if locked {
+                match recovery_manager.lock_period(Some(recovery.block)) {
+                    Ok(s) => Some(s.as_u64()),
+                    Err(_e) => {
+                        if current.is_none() {
+                            current = recovery_manager.lock_period(None).ok();
+                        }
+                        estimated = true;
+                        current.map(|period| period.as_u64())
+                    }
+                }
+            } else {
+                None
+            };
+
+            events.push(LockEvent {
+                locked,
+                module: recovery_manager.address,
+                release_after: lock_period.map(|period| recovery.timestamp + period),
+                estimated,
+                block: recovery.block,
+                log_index: recovery.log_index,
+                timestamp: recovery.timestamp,
+                tx: recovery.tx,
+                sender: recovery.sender,
+            });
+        }
+
+        Ok(events)
+    }
+
+    fn encode(&self, method: &str, wallet: Address) -> ethabi::Bytes {
+        self.module
+            .encode(method, &[ethabi::Token::Address(wallet)])
+            .unwrap()
+    }
+}
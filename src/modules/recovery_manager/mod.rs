@@ -1,300 +1,555 @@
 use crate::constants;
 use crate::helpers;
-use crate::modules::Relayer;
-use std::str::FromStr;
-use tiny_keccak::{Hasher, Sha3, Keccak};
-// extern crate rustc_hex;
-
-// use hex::ToHex;
-use rustc_hex::ToHex;
-
-// use rustc_hex::{FromHex, ToHex};
+use crate::modules::{Gas, Outcome, RelayedModule, Relayer};
+use crate::network;
+use crate::session::Session;
+use crate::signer::Signer;
+use crate::wallet::Wallet;
 use web3::api::Web3;
 use web3::contract::Contract;
 use web3::contract::Options;
 use web3::futures::Future;
-use web3::types::{Address, Bytes, CallRequest, TransactionRequest, H160, H256, U256};
+use web3::types::{Address, BlockNumber, FilterBuilder, Log, H256, U256};
+
+#[derive(Clone, Debug)]
+pub struct RecoveryStatus {
+    pub recovery: Address,
+    pub execute_after: u64,
+    pub guardian_count: u32,
+    pub recovery_period: U256,
+    pub lock_period: U256,
+    pub locked: bool,
+}
+
+#[derive(Clone, Debug)]
+pub struct RecoveryEvent {
+    pub name: String,
+    pub owner: Address,
+    pub execute_after: Option<u64>,
+    pub block: u64,
+    pub log_index: u64,
+    pub timestamp: u64,
+    pub tx: H256,
+    pub sender: Address,
+}
 
 #[derive(Clone, Debug)]
 pub struct RecoveryManager<'a, T: web3::Transport> {
     pub address: Address,
     abi: ethabi::Contract,
     contract: web3::contract::Contract<T>,
+    module: RelayedModule<'a, T>,
     web3: &'a Web3<T>,
 }
 
 impl<'a, T: web3::Transport> RecoveryManager<'a, T> {
-    pub fn new(web3: &'a Web3<T>) -> Self {
-        RecoveryManager::<'a, T> {
-            address: Address::from_str(&"dfa1468d07fc86840a6eb53e0e65cebde81d1af9").unwrap(),
+    pub fn new(web3: &'a Web3<T>) -> Result<Self, String> {
+        let address = match network::current().module("RecoveryManager") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        Ok(RecoveryManager::<'a, T> {
+            address,
             abi: ethabi::Contract::load(constants::abis::RECOVERY_MANAGER).unwrap(),
-            contract: Contract::from_json(
-                web3.eth(),
-                Address::from_str(&"dfa1468d07fc86840a6eb53e0e65cebde81d1af9").unwrap(),
-                constants::abis::RECOVERY_MANAGER,
-            )
-            .unwrap(),
+            contract: Contract::from_json(web3.eth(), address, constants::abis::RECOVERY_MANAGER)
+                .unwrap(),
+            module: RelayedModule::new(address, constants::abis::RECOVERY_MANAGER, web3).unwrap(),
             web3,
-        }
+        })
+    }
+
+    pub fn with_gas(mut self, gas: Gas) -> Self {
+        self.module = self.module.with_gas(gas);
+        self
+    }
+
+    pub fn with_relayer(mut self, relayer: &'a dyn Signer) -> Self {
+        self.module = self.module.with_relayer(relayer);
+        self
+    }
+
+    pub fn initialize(&self, wallet: Address, new_owner: Address) -> Result<Session, String> {
+        let data = self.encode_initialize_recovery(wallet, new_owner);
+
+        self.session(wallet, data)
+    }
+
+    pub fn cancel_recovery(&self, wallet: Address) -> Result<Session, String> {
+        let data = self.encode_cancel_recovery(wallet);
+
+        self.session(wallet, data)
+    }
+
+    pub fn transfer_ownership(
+        &self,
+        wallet: Address,
+        new_owner: Address,
+    ) -> Result<Session, String> {
+        let data = self.encode_execute_ownership_transfer(wallet, new_owner);
+
+        self.session(wallet, data)
+    }
+
+    pub fn cancel_ownership_transfer(&self, wallet: Address) -> Result<Session, String> {
+        let data = self.encode_cancel_ownership_transfer(wallet);
+
+        self.session(wallet, data)
     }
 
-    pub fn initialize(&self, wallet: Address, new_owner: Address) -> Result<H256, String> {
-        let accounts = match self.web3.eth().accounts().wait() {
+    pub fn sign(&self, session: &mut Session, signer: &dyn Signer) -> Result<(), String> {
+        let wallet = Wallet::new(session.wallet, self.web3);
+
+        let mut signers = match wallet.guardians() {
             Ok(s) => s,
-            Err(_e) => {
-                return Err(String::from(
-                    "modules :: recovery_manager :: initialize :: unable to fetch accounts",
-                ))
+            Err(e) => return Err(e),
+        };
+
+        // the owner can take part in every operation but the execution of a recovery
+        if self.method(session) != Some("executeRecovery") {
+            match wallet.owner() {
+                Ok(s) => signers.push(s),
+                Err(e) => return Err(e),
             }
+        }
+
+        if !signers.contains(&signer.address()) {
+            return Err(format!(
+                "{:?} is not allowed to sign for {:?}",
+                signer.address(),
+                session.wallet
+            ));
+        }
+
+        self.module.sign(session, signer)
+    }
+
+    pub fn required_signatures(&self, session: &Session) -> Result<usize, String> {
+        let wallet = Wallet::new(session.wallet, self.web3);
+
+        let guardians = match wallet.guardians() {
+            Ok(s) => s.len(),
+            Err(e) => return Err(e),
         };
 
-        let nonce = match self.nonce() {
+        match self.method(session) {
+            Some("executeRecovery") => Ok((guardians + 1) / 2),
+            Some("cancelRecovery") => match self.get_recovery(session.wallet) {
+                Ok((_, _, count)) => Ok((count as usize + 2) / 2),
+                Err(e) => Err(e),
+            },
+            Some("executeOwnershipTransfer") => Ok((guardians + 1) / 2 + 1),
+            Some("cancelOwnershipTransfer") => Ok(1),
+            _ => Err(format!("unknown operation for {:?}", session.wallet)),
+        }
+    }
+
+    pub fn simulate(&self, session: &Session) -> Result<Outcome, String> {
+        let owner = match Wallet::new(session.wallet, self.web3).owner() {
             Ok(s) => s,
             Err(e) => return Err(e),
         };
 
-        let data = self.encode_initialize_recovery(wallet, new_owner);
+        self.module.simulate(session, owner)
+    }
 
-        let hash_sign = match self.hash_sign_2(wallet, data.clone(), nonce) {
+    pub fn submit(&self, session: &Session) -> Result<H256, String> {
+        let wallet = Wallet::new(session.wallet, self.web3);
+
+        let owner = match wallet.owner() {
             Ok(s) => s,
             Err(e) => return Err(e),
         };
 
-        let signature = helpers::sign(accounts[0], hash_sign, self.web3).unwrap();
-
-        self.execute(wallet, data, nonce, signature.as_bytes())
+        self.module.submit(session, owner)
     }
 
-    pub fn cancel_recovery(&self, wallet: Address) -> Result<H256, String> {
-        let accounts = match self.web3.eth().accounts().wait() {
+    pub fn finalize_recovery(&self, wallet: Address) -> Result<Session, String> {
+        let (recovery, execute_after, _) = match self.get_recovery(wallet) {
             Ok(s) => s,
-            Err(_e) => return Err(String::from("unable to fetch accounts")),
+            Err(e) => return Err(e),
         };
 
-        let nonce = match self.nonce() {
-            Ok(s) => s,
+        if recovery == Address::zero() {
+            return Err(format!("no ongoing recovery for {:?}", wallet));
+        }
+
+        let now = match helpers::timestamp(self.web3) {
+            Ok(s) => s.as_u64(),
             Err(e) => return Err(e),
         };
 
-        println!("Nonce {:?}", nonce);
+        if now < execute_after {
+            return Err(format!(
+                "recovery period for {:?} is not over yet [{} left]",
+                wallet,
+                helpers::duration(execute_after - now)
+            ));
+        }
 
-        let data = self.encode_cancel_recovery(wallet);
-        // wallet: Address, value: U256, data: ethabi::Bytes, nonce: U256, gas_price: U256, gas_limit: U256)
-        let hash_sign = match self.hash_sign_2(wallet, data.clone(), nonce) {
+        let data = self.encode_finalize_recovery(wallet);
+
+        // finalizeRecovery does not require any signature: anyone can relay it
+        self.module.prepare(wallet, data)
+    }
+
+    pub fn recovery_finalized(&self, tx: H256) -> Result<Address, String> {
+        let log = match self.event(tx, "RecoveryFinalized") {
             Ok(s) => s,
             Err(e) => return Err(e),
         };
 
-        let signature = helpers::sign(accounts[0], hash_sign, self.web3).unwrap();
-
-        self.execute(wallet, data, nonce, signature.as_bytes())
+        match log.params[1].value {
+            ethabi::Token::Address(s) => Ok(s),
+            _ => Err(format!("invalid RecoveryFinalized event in {:?}", tx)),
+        }
     }
 
-    fn hash_sign(
-        &self,
-        wallet: Address,
-        data: ethabi::Bytes,
-        nonce: U256,
-    ) -> Result<Bytes, String> {
-        let options = Options::default();
-
-        let erc_1077_hash_abi = ethabi::Contract::load(constants::abis::ERC1077_HASH).unwrap();
-        let erc_1077_hash_address =
-            Address::from_str(&"83649266Ba0a8CAd860403a6532F12c6074BBDAC").unwrap();
-
-        let function = erc_1077_hash_abi.function("getSignHash").unwrap();
-        let params: [ethabi::Token; 7] = [
-            ethabi::Token::Address(self.address),
-            ethabi::Token::Address(wallet),
-            ethabi::Token::Uint(self.value()),
-            ethabi::Token::Bytes(data.clone()),
-            ethabi::Token::Uint(nonce),
-            ethabi::Token::Uint(self.gas_price()),
-            ethabi::Token::Uint(self.gas_limit()),
-        ];
+    pub fn finalize_ownership_transfer(&self, wallet: Address) -> Result<Session, String> {
+        let (_, execute_after) = match self.ownership_transfer(wallet) {
+            Ok(Some(s)) => s,
+            Ok(None) => return Err(format!("no ongoing ownership transfer for {:?}", wallet)),
+            Err(e) => return Err(e),
+        };
 
-        println!("params {:#?}", params);
-        let encoded = function.encode_input(&params).unwrap();
-
-        let result = self.web3.eth().call(
-            CallRequest {
-                from: None,
-                to: erc_1077_hash_address,
-                gas: options.gas,
-                gas_price: options.gas_price,
-                value: options.value,
-                data: Some(Bytes(encoded)),
-            },
-            None.into(),
-        );
-
-        let result = match result.wait() {
-          Ok(s) => Ok(s),
-          Err(_e) => Err(format!("unable to fetch hash sign")),
-      };
-
-      println!("From contract {:?}", result.clone().unwrap());
-
-        // begin just get the packed data
-        let function = erc_1077_hash_abi.function("justHash").unwrap();
-        let encoded = function.encode_input(&params).unwrap();
-
-        let packed = self.web3.eth().call(
-            CallRequest {
-                from: None,
-                to: erc_1077_hash_address,
-                gas: options.gas,
-                gas_price: options.gas_price,
-                value: options.value,
-                data: Some(Bytes(encoded.clone())),
-            },
-            None.into(),
-        );
+        let now = match helpers::timestamp(self.web3) {
+            Ok(s) => s.as_u64(),
+            Err(e) => return Err(e),
+        };
 
-        let packed = packed.wait().unwrap();
-        // println!("Data {:?}", data.clone());
-        println!("Packed 1 {:?}", packed);
+        if now < execute_after {
+            return Err(format!(
+                "security period for {:?} is not over yet [{} left]",
+                wallet,
+                helpers::duration(execute_after - now)
+            ));
+        }
 
-        self.just_hash(packed.0);
-        // end - just get the packed data
+        let data = self.encode_finalize_ownership_transfer(wallet);
 
-        // match result.wait() {
-        //     Ok(s) => Ok(s),
-        //     Err(_e) => Err(format!("unable to fetch hash sign")),
-        // }
+        // the owner and guardians signed the transfer when it was executed: once the security
+        // period is over there is nothing left to approve
+        self.module.prepare(wallet, data)
+    }
 
+    pub fn ownership_transfer(&self, wallet: Address) -> Result<Option<(Address, u64)>, String> {
+        let logs = match self.logs(
+            wallet,
+            &[
+                "OwnershipTransferExecuted",
+                "OwnershipTransferFinalized",
+                "OwnershipTransferCanceled",
+            ],
+        ) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        // keccak start
+        let mut pending = None;
+
+        for (name, log, _) in logs.iter() {
+            if name == "OwnershipTransferExecuted" {
+                if let (ethabi::Token::Address(owner), ethabi::Token::Uint(execute_after)) =
+                    (&log.params[1].value, &log.params[2].value)
+                {
+                    pending = Some((*owner, execute_after.as_u64()));
+                }
+            } else {
+                pending = None;
+            }
+        }
 
-        let mut sha3 = Keccak::v256();
-        let mut hash = [0u8; 32];
+        Ok(pending)
+    }
 
-        let mut packed = Vec::<u8>::new();
-        
-        // let pre = b"0x".as_bytes();
-        let prefix = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 206, 25, 0];
-        let padding = [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+    pub fn history(&self, wallet: Address) -> Result<Vec<RecoveryEvent>, String> {
+        let logs = match self.logs(
+            wallet,
+            &[
+                "RecoveryExecuted",
+                "RecoveryFinalized",
+                "RecoveryCanceled",
+                "OwnershipTransferExecuted",
+                "OwnershipTransferFinalized",
+                "OwnershipTransferCanceled",
+            ],
+        ) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        // packed.push(*hex::decode("19").unwrap().first().unwrap());
-        // packed.push(*hex::decode("0").unwrap().first().unwrap());
-        // packed = [prefix, *self.address.as_bytes()].concat();
+        let mut events = Vec::<RecoveryEvent>::new();
+
+        for (name, log, raw) in logs.iter() {
+            let block = raw.block_number.unwrap_or_default().as_u64();
+            let tx = raw.transaction_hash.unwrap_or_default();
+
+            let timestamp = match helpers::block_timestamp(block, self.web3) {
+                Ok(s) => s,
+                Err(e) => return Err(e),
+            };
+
+            let sender = match helpers::sender(tx, self.web3) {
+                Ok(s) => s,
+                Err(e) => return Err(e),
+            };
+
+            let owner = match log.params[1].value {
+                ethabi::Token::Address(s) => s,
+                _ => Address::zero(),
+            };
+
+            let execute_after = match log.params.get(2).map(|param| &param.value) {
+                Some(ethabi::Token::Uint(s)) => Some(s.as_u64()),
+                _ => None,
+            };
+
+            events.push(RecoveryEvent {
+                name: name.clone(),
+                owner,
+                execute_after,
+                block,
+                log_index: raw.log_index.unwrap_or_default().as_u64(),
+                timestamp,
+                tx,
+                sender,
+            });
+        }
 
-        let value: [u8; 32] = self.value().into();
-        let gas_price: [u8; 32] = self.gas_price().into();
-        let gas_limit: [u8; 32] = self.gas_limit().into();
-        let nonce: [u8; 32] = nonce.into();
+        Ok(events)
+    }
 
-        // let t = ;
-        let t = "0x".as_bytes().to_hex::<String>();
+    pub fn ownership_transfer_executed(&self, tx: H256) -> Result<(Address, u64), String> {
+        let log = match self.event(tx, "OwnershipTransferExecuted") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        // packed.extend_from_slice(t.as_bytes());
-        // packed.extend_from_slice(&prefix);
-        packed.extend_from_slice(b"\x19");
-        packed.extend_from_slice(b"\x00");
-        packed.extend_from_slice(self.address.as_bytes());
-        packed.extend_from_slice(wallet.as_bytes());
-        packed.extend_from_slice(&value);
-        packed.extend_from_slice(&data);
-        packed.extend_from_slice(&nonce);
-        packed.extend_from_slice(&gas_price);
-        packed.extend_from_slice(&gas_limit);
-        // packed.extend_from_slice(&padding); 
+        match (&log.params[1].value, &log.params[2].value) {
+            (ethabi::Token::Address(owner), ethabi::Token::Uint(execute_after)) => {
+                Ok((*owner, execute_after.as_u64()))
+            }
+            _ => Err(format!(
+                "invalid OwnershipTransferExecuted event in {:?}",
+                tx
+            )),
+        }
+    }
 
-        // println!("Packed 2 {:?}", packed);
+    pub fn ownership_transfer_finalized(&self, tx: H256) -> Result<Address, String> {
+        let log = match self.event(tx, "OwnershipTransferFinalized") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        // sha3.update(b"0x");
+        match log.params[1].value {
+            ethabi::Token::Address(s) => Ok(s),
+            _ => Err(format!(
+                "invalid OwnershipTransferFinalized event in {:?}",
+                tx
+            )),
+        }
+    }
 
-        // let packed2 = packed.as_slice().to_hex::<String>();
+    pub fn ownership_transfer_canceled(&self, tx: H256) -> Result<Address, String> {
+        let log = match self.event(tx, "OwnershipTransferCanceled") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        // sha3.update(packed2.as_bytes());
-        sha3.update(packed.as_slice());
+        match log.params[1].value {
+            ethabi::Token::Address(s) => Ok(s),
+            _ => Err(format!(
+                "invalid OwnershipTransferCanceled event in {:?}",
+                tx
+            )),
+        }
+    }
 
-// 
- 
+    pub fn status(&self, wallet: Address) -> Result<RecoveryStatus, String> {
+        let (recovery, execute_after, guardian_count) = match self.get_recovery(wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        sha3.finalize(&mut hash);
+        let result = self
+            .contract
+            .query("recoveryPeriod", (), None, Options::default(), None);
+        let recovery_period = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(String::from("unable to fetch recovery period")),
+        };
 
-        println!("hashed {:?}", hash);
+        let lock_period = match self.lock_period(None) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        // self.just_hash(packed.to_vec());
-  
-        
+        let locked = match self.locked_by_recovery(wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        result
+        Ok(RecoveryStatus {
+            recovery,
+            execute_after,
+            guardian_count,
+            recovery_period,
+            lock_period,
+            locked,
+        })
     }
 
+    // lock period in force at a block, the latest one by default
+    pub fn lock_period(&self, block: Option<u64>) -> Result<U256, String> {
+        let block = block.map(|block| BlockNumber::Number(block.into()));
+        let result = self
+            .contract
+            .query("lockPeriod", (), None, Options::default(), block);
 
-    fn hash_sign_2(
-        &self,
-        wallet: Address,
-        data: ethabi::Bytes,
-        nonce: U256,
-    ) -> Result<Bytes, String> {
-        let mut sha3 = Keccak::v256();
-        let mut hash = [0u8; 32];
+        match result.wait() {
+            Ok(s) => Ok(s),
+            Err(_e) => Err(String::from("unable to fetch lock period")),
+        }
+    }
 
-        let mut packed = Vec::<u8>::new();
+    fn locked_by_recovery(&self, wallet: Address) -> Result<bool, String> {
+        let result = self
+            .contract
+            .query("guardianStorage", (), None, Options::default(), None);
+        let guardian_storage: Address = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(String::from("unable to fetch guardian storage address")),
+        };
+        let guardian_storage = Contract::from_json(
+            self.web3.eth(),
+            guardian_storage,
+            constants::abis::GUARDIAN_STORAGE,
+        )
+        .unwrap();
+
+        let result = guardian_storage.query("isLocked", (wallet,), None, Options::default(), None);
+        let locked: bool = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to fetch lock for {:?}", wallet)),
+        };
 
-        let value: [u8; 32] = self.value().into();
-        let gas_price: [u8; 32] = self.gas_price().into();
-        let gas_limit: [u8; 32] = self.gas_limit().into();
-        let nonce: [u8; 32] = nonce.into();
+        let result = guardian_storage.query("getLocker", (wallet,), None, Options::default(), None);
+        let locker: Address = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to fetch locker for {:?}", wallet)),
+        };
 
+        Ok(locked && locker == self.address)
+    }
 
-        packed.extend_from_slice(b"\x19");
-        packed.extend_from_slice(b"\x00");
-        packed.extend_from_slice(self.address.as_bytes());
-        packed.extend_from_slice(wallet.as_bytes());
-        packed.extend_from_slice(&value);
-        packed.extend_from_slice(&data);
-        packed.extend_from_slice(&nonce);
-        packed.extend_from_slice(&gas_price);
-        packed.extend_from_slice(&gas_limit);
+    fn session(&self, wallet: Address, data: ethabi::Bytes) -> Result<Session, String> {
+        match Session::load(self.address, wallet) {
+            Ok(Some(s)) => {
+                if s.data.0 == data {
+                    return Ok(s);
+                } else {
+                    return Err(format!(
+                        "another operation is pending for {:?}: use --reset to discard it",
+                        wallet
+                    ));
+                }
+            }
+            Ok(None) => (),
+            Err(e) => return Err(e),
+        };
 
-        sha3.update(packed.as_slice());
-        sha3.finalize(&mut hash);
+        self.module.prepare(wallet, data)
+    }
 
-        println!("hashed {:?}", hash);
+    fn method(&self, session: &Session) -> Option<&str> {
+        self.module.method(&session.data.0)
+    }
 
-        // self.just_hash(packed.to_vec());
-  
-        
+    fn event(&self, tx: H256, name: &str) -> Result<ethabi::Log, String> {
+        let receipt = match helpers::receipt(tx, self.web3) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        Ok(Bytes(hash.to_vec()))
+        let event = self.abi.event(name).unwrap();
+
+        for log in receipt
+            .logs
+            .iter()
+            .filter(|log| log.address == self.address)
+        {
+            let log = event.parse_log(ethabi::RawLog {
+                topics: log.topics.clone(),
+                data: log.data.0.clone(),
+            });
+
+            if let Ok(log) = log {
+                return Ok(log);
+            }
+        }
+
+        Err(format!("no {} event in {:?}", name, tx))
     }
 
-    fn just_hash(&self, data: Vec<u8>) {
-        let options = Options::default();
+    fn logs(
+        &self,
+        wallet: Address,
+        names: &[&str],
+    ) -> Result<Vec<(String, ethabi::Log, Log)>, String> {
+        let events: Vec<&ethabi::Event> = names
+            .iter()
+            .map(|name| self.abi.event(name).unwrap())
+            .collect();
+
+        let filter = FilterBuilder::default()
+            .address(vec![self.address])
+            .topics(
+                Some(events.iter().map(|event| event.signature()).collect()),
+                Some(vec![H256::from(wallet)]),
+                None,
+                None,
+            )
+            .from_block(network::current().start_block.into())
+            .build();
 
-        let erc_1077_hash_abi = ethabi::Contract::load(constants::abis::ERC1077_HASH).unwrap();
-        let erc_1077_hash_address =
-            Address::from_str(&"83649266Ba0a8CAd860403a6532F12c6074BBDAC").unwrap();
+        let logs = match self.web3.eth().logs(filter).wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to fetch recovery logs for {:?}", wallet)),
+        };
 
-        let function = erc_1077_hash_abi.function("doKeccak").unwrap();
-        let params: [ethabi::Token; 1] = [
-            ethabi::Token::Bytes(data),
-        ];
-        let encoded = function.encode_input(&params).unwrap();
-
-        let result = self.web3.eth().call(
-            CallRequest {
-                from: None,
-                to: erc_1077_hash_address,
-                gas: options.gas,
-                gas_price: options.gas_price,
-                value: options.value,
-                data: Some(Bytes(encoded)),
-            },
-            None.into(),
-        );
+        let mut parsed = Vec::new();
 
-        let result = match result.wait() {
-          Ok(s) => Ok(s),
-          Err(_e) => Err(format!("unable to fetch just hash sign")),
-      };
+        for log in logs.into_iter() {
+            let event = events
+                .iter()
+                .find(|event| Some(&event.signature()) == log.topics.first());
 
-      println!("Just hash {:?}", result);
+            if let Some(event) = event {
+                let result = event.parse_log(ethabi::RawLog {
+                    topics: log.topics.clone(),
+                    data: log.data.0.clone(),
+                });
 
+                match result {
+                    Ok(s) => parsed.push((event.name.clone(), s, log)),
+                    Err(_e) => return Err(format!("unable to decode {} log", event.name)),
+                }
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn get_recovery(&self, wallet: Address) -> Result<(Address, u64, u32), String> {
+        let result = self
+            .contract
+            .query("getRecovery", (wallet,), None, Options::default(), None);
+
+        match result.wait() {
+            Ok(s) => Ok(s),
+            Err(_e) => Err(format!("unable to fetch recovery for {:?}", wallet)),
+        }
     }
 
     fn encode_initialize_recovery(&self, wallet: Address, new_owner: Address) -> ethabi::Bytes {
@@ -313,55 +568,39 @@ impl<'a, T: web3::Transport> RecoveryManager<'a, T> {
 
         function.encode_input(&params).unwrap()
     }
-}
 
-impl<'a, T: web3::Transport> Relayer<T> for RecoveryManager<'a, T> {
-    fn execute(
-        &self,
-        wallet: Address,
-        data: ethabi::Bytes,
-        nonce: U256,
-        signature: &[u8],
-    ) -> Result<H256, String> {
-        let options = Options::default();
+    fn encode_finalize_recovery(&self, wallet: Address) -> ethabi::Bytes {
+        let function = self.abi.function("finalizeRecovery").unwrap();
+        let params: [ethabi::Token; 1] = [ethabi::Token::Address(wallet)];
 
-        let accounts = match self.web3.eth().accounts().wait() {
-            Ok(s) => s,
-            Err(_e) => return Err(String::from("unable to fetch accounts")),
-        };
+        function.encode_input(&params).unwrap()
+    }
 
-        let function = self.abi.function("execute").unwrap();
-        let params: [ethabi::Token; 6] = [
+    fn encode_execute_ownership_transfer(
+        &self,
+        wallet: Address,
+        new_owner: Address,
+    ) -> ethabi::Bytes {
+        let function = self.abi.function("executeOwnershipTransfer").unwrap();
+        let params: [ethabi::Token; 2] = [
             ethabi::Token::Address(wallet),
-            ethabi::Token::Bytes(data),
-            ethabi::Token::Uint(nonce),
-            ethabi::Token::Bytes(signature.to_vec()),
-            ethabi::Token::Uint(self.gas_price()),
-            ethabi::Token::Uint(self.gas_limit()),
+            ethabi::Token::Address(new_owner),
         ];
-        let encoded = function.encode_input(&params).unwrap();
-
-        let tx = self.web3.eth().send_transaction(TransactionRequest {
-            from: accounts[0],
-            to: Some(self.address),
-            gas: options.gas,
-            gas_price: options.gas_price,
-            value: options.value,
-            nonce: options.nonce,
-            data: Some(Bytes(encoded)),
-            condition: options.condition,
-        });
-
-        match tx.wait() {
-            Ok(s) => Ok(s),
-            Err(e) => Err(format!(
-                "unable to initialize recovery for {:?}: {}",
-                self.address, e
-            )),
-        }
+
+        function.encode_input(&params).unwrap()
     }
 
-    fn web3(&self) -> &Web3<T> {
-        self.web3
+    fn encode_finalize_ownership_transfer(&self, wallet: Address) -> ethabi::Bytes {
+        let function = self.abi.function("finalizeOwnershipTransfer").unwrap();
+        let params: [ethabi::Token; 1] = [ethabi::Token::Address(wallet)];
+
+        function.encode_input(&params).unwrap()
+    }
+
+    fn encode_cancel_ownership_transfer(&self, wallet: Address) -> ethabi::Bytes {
+        let function = self.abi.function("cancelOwnershipTransfer").unwrap();
+        let params: [ethabi::Token; 1] = [ethabi::Token::Address(wallet)];
+
+        function.encode_input(&params).unwrap()
     }
 }
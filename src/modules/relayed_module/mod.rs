@@ -0,0 +1,292 @@
+use crate::constants;
+use crate::helpers;
+use crate::modules::{Fees, Gas, Relayer};
+use crate::session::Session;
+use crate::sign_hash;
+use crate::signer::Signer;
+use web3::api::Web3;
+use web3::contract::Options;
+use web3::futures::Future;
+use web3::types::{Address, Bytes, CallRequest, TransactionRequest, H256, U256};
+
+// outcome of a relayed transaction, as the TransactionExecuted event would report it, or the
+// reason why execute itself reverted
+#[derive(Clone, Debug)]
+pub enum Outcome {
+    Executed {
+        success: bool,
+        sign_hash: H256,
+        reason: Option<String>,
+    },
+    Reverted(String),
+}
+
+// any module relaying transactions through execute: it only needs the address and the ABI of
+// the module, execute itself coming from the common relayer interface
+#[derive(Clone, Debug)]
+pub struct RelayedModule<'a, T: web3::Transport> {
+    pub address: Address,
+    pub abi: ethabi::Contract,
+    relayer: ethabi::Contract,
+    gas: Gas,
+    sender: Option<&'a dyn Signer>,
+    web3: &'a Web3<T>,
+}
+
+impl<'a, T: web3::Transport> RelayedModule<'a, T> {
+    pub fn new(address: Address, abi: &[u8], web3: &'a Web3<T>) -> Result<Self, String> {
+        let abi = match ethabi::Contract::load(abi) {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("invalid ABI for module {:?}", address)),
+        };
+
+        Ok(RelayedModule::<'a, T> {
+            address,
+            abi,
+            relayer: ethabi::Contract::load(constants::abis::RELAYER_MODULE).unwrap(),
+            gas: Gas::default(),
+            sender: None,
+            web3,
+        })
+    }
+
+    pub fn with_gas(mut self, gas: Gas) -> Self {
+        self.gas = gas;
+        self
+    }
+
+    // account sending the relayed transactions
+    pub fn with_relayer(mut self, relayer: &'a dyn Signer) -> Self {
+        self.sender = Some(relayer);
+        self
+    }
+
+    pub fn encode(&self, method: &str, params: &[ethabi::Token]) -> Result<ethabi::Bytes, String> {
+        let function = match self.abi.function(method) {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unknown method {} for {:?}", method, self.address)),
+        };
+
+        match function.encode_input(params) {
+            Ok(s) => Ok(s),
+            Err(_e) => Err(format!("invalid parameters for {}", method)),
+        }
+    }
+
+    // name of the method called by some relayed data, if it belongs to the module
+    pub fn method(&self, data: &[u8]) -> Option<&str> {
+        if data.len() < 4 {
+            return None;
+        }
+
+        self.abi
+            .functions()
+            .find(|function| helpers::selector(function) == data[..4])
+            .map(|function| function.name.as_str())
+    }
+
+    pub fn sign_hash(&self, wallet: Address, data: &[u8], nonce: U256, fees: &Fees) -> H256 {
+        sign_hash::sign_hash(
+            self.address,
+            wallet,
+            self.value(),
+            data,
+            nonce,
+            fees.gas_price,
+            fees.gas_limit,
+        )
+    }
+
+    // signs the relayed transaction as is: checking that the signer is allowed to is up to the
+    // caller, as it depends on the method
+    pub fn sign(&self, session: &mut Session, signer: &dyn Signer) -> Result<(), String> {
+        let hash = self.sign_hash(
+            session.wallet,
+            &session.data.0,
+            session.nonce,
+            &session.fees,
+        );
+
+        if session.module != self.address || session.hash.0 != hash.as_bytes() {
+            return Err(format!(
+                "session does not match a transaction relayed through {:?}",
+                self.address
+            ));
+        }
+
+        let signature = match signer.sign(&session.hash.0) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        session.add(signer.address(), signature)
+    }
+
+    // eth_call of execute with the exact payload submit would send
+    pub fn simulate(&self, session: &Session, owner: Address) -> Result<Outcome, String> {
+        let relayer = match self.sender() {
+            Ok(s) => s.address(),
+            Err(e) => return Err(e),
+        };
+
+        let function = self.relayer.function("execute").unwrap();
+        let encoded = self.encode_execute(
+            session.wallet,
+            session.data.0.clone(),
+            session.nonce,
+            &session.signatures(owner),
+            &session.fees,
+        );
+
+        let result = self.web3.eth().call(
+            CallRequest {
+                from: Some(relayer),
+                to: self.address,
+                gas: None,
+                gas_price: self.gas_price(&session.fees),
+                value: None,
+                data: Some(Bytes(encoded)),
+            },
+            None,
+        );
+
+        let output = match result.wait() {
+            Ok(s) => s,
+            Err(e) => return Ok(Outcome::Reverted(helpers::revert_reason(&e))),
+        };
+
+        let success = match function.decode_output(&output.0) {
+            Ok(tokens) => match tokens.first() {
+                Some(ethabi::Token::Bool(s)) => *s,
+                _ => return Err(String::from("unable to decode execute output")),
+            },
+            Err(_e) => return Err(String::from("unable to decode execute output")),
+        };
+
+        // execute swallows the failure of the inner call: replay it alone to get its reason
+        let reason = if success {
+            None
+        } else {
+            let result = self.web3.eth().call(
+                CallRequest {
+                    from: Some(self.address),
+                    to: self.address,
+                    gas: None,
+                    gas_price: None,
+                    value: None,
+                    data: Some(session.data.clone()),
+                },
+                None,
+            );
+
+            match result.wait() {
+                Ok(_) => None,
+                Err(e) => Some(helpers::revert_reason(&e)),
+            }
+        };
+
+        Ok(Outcome::Executed {
+            success,
+            sign_hash: sign_hash::prefix(H256::from_slice(&session.hash.0)),
+            reason,
+        })
+    }
+
+    pub fn submit(&self, session: &Session, owner: Address) -> Result<H256, String> {
+        self.execute(
+            session.wallet,
+            session.data.0.clone(),
+            session.nonce,
+            &session.signatures(owner),
+            &session.fees,
+        )
+    }
+
+    fn encode_execute(
+        &self,
+        wallet: Address,
+        data: ethabi::Bytes,
+        nonce: U256,
+        signature: &[u8],
+        fees: &Fees,
+    ) -> ethabi::Bytes {
+        let function = self.relayer.function("execute").unwrap();
+        let params: [ethabi::Token; 6] = [
+            ethabi::Token::Address(wallet),
+            ethabi::Token::Bytes(data),
+            ethabi::Token::Uint(nonce),
+            ethabi::Token::Bytes(signature.to_vec()),
+            ethabi::Token::Uint(fees.gas_price),
+            ethabi::Token::Uint(fees.gas_limit),
+        ];
+
+        function.encode_input(&params).unwrap()
+    }
+
+    fn sender(&self) -> Result<&'a dyn Signer, String> {
+        match self.sender {
+            Some(s) => Ok(s),
+            None => Err(format!("no account to relay through {:?}", self.address)),
+        }
+    }
+
+    // in refund mode the relayer has to pay the gas price it is refunded at
+    fn gas_price(&self, fees: &Fees) -> Option<U256> {
+        if fees.gas_price.is_zero() {
+            self.gas.price
+        } else {
+            Some(fees.gas_price)
+        }
+    }
+}
+
+impl<'a, T: web3::Transport> Relayer<T> for RelayedModule<'a, T> {
+    fn execute(
+        &self,
+        wallet: Address,
+        data: ethabi::Bytes,
+        nonce: U256,
+        signature: &[u8],
+        fees: &Fees,
+    ) -> Result<H256, String> {
+        let options = Options::default();
+
+        let relayer = match self.sender() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        let encoded = self.encode_execute(wallet, data, nonce, signature, fees);
+
+        let tx = relayer.send_transaction(TransactionRequest {
+            from: relayer.address(),
+            to: Some(self.address),
+            gas: options.gas,
+            gas_price: self.gas_price(fees),
+            value: options.value,
+            nonce: options.nonce,
+            data: Some(Bytes(encoded)),
+            condition: options.condition,
+        });
+
+        match tx {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!(
+                "unable to relay transaction through {:?}: {}",
+                self.address, e
+            )),
+        }
+    }
+
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn gas(&self) -> &Gas {
+        &self.gas
+    }
+
+    fn web3(&self) -> &Web3<T> {
+        self.web3
+    }
+}
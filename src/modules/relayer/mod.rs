@@ -1,7 +1,35 @@
+use crate::constants;
+use crate::session::Session;
+use crate::sign_hash;
 use ethabi::Bytes as ABIBytes;
+use serde::{Deserialize, Serialize};
 use web3::api::Web3;
+use web3::contract::{Contract, Options};
 use web3::futures::Future;
-use web3::types::{Address, BlockId, BlockNumber, H256, U256};
+use web3::types::{Address, Bytes, CallRequest, H256, U256};
+
+// gas settings requested by the user: the gas price is only signed into the relayed transaction,
+// and thus refunded by the wallet, in refund mode
+#[derive(Clone, Debug, Default)]
+pub struct Gas {
+    pub price: Option<U256>,
+    pub limit: Option<U256>,
+    pub refund: bool,
+}
+
+// gas price and gas limit signed into a relayed transaction
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct Fees {
+    pub gas_price: U256,
+    pub gas_limit: U256,
+}
+
+impl Fees {
+    // the most the wallet can pay back to the relayer
+    pub fn refund(&self) -> U256 {
+        self.gas_price.saturating_mul(self.gas_limit)
+    }
+}
 
 pub trait Relayer<T: web3::Transport> {
     fn execute(
@@ -10,36 +38,166 @@ pub trait Relayer<T: web3::Transport> {
         data: ABIBytes,
         nonce: U256,
         signature: &[u8],
+        fees: &Fees,
     ) -> Result<H256, String>;
 
-    fn web3(&self) -> &Web3<T>;
+    fn address(&self) -> Address;
 
-    fn nonce(&self) -> Result<U256, String> {
-        let block = match self
-            .web3()
-            .eth()
-            .block(BlockId::Number(BlockNumber::Latest))
-            .wait()
-        {
-            Ok(s) => s.unwrap(),
-            Err(_e) => return Err(format!("unable to fetch last block")),
-        };
+    fn gas(&self) -> &Gas;
 
-        let nonce = format!("{:?}{:?}", block.number.unwrap(), block.timestamp);
-        let nonce = U256::from_dec_str(&nonce).unwrap();
+    fn web3(&self) -> &Web3<T>;
 
-        Ok(nonce)
+    fn nonce(&self, wallet: Address) -> Result<U256, String> {
+        nonce(self.address(), wallet, self.web3())
     }
 
     fn value(&self) -> U256 {
         U256::from(0u32)
     }
 
-    fn gas_price(&self) -> U256 {
-        U256::from(0u32)
+    fn fees(&self, data: &[u8]) -> Result<Fees, String> {
+        let gas = self.gas();
+
+        let gas_price = match (gas.refund, gas.price) {
+            (false, _) => U256::zero(),
+            (true, Some(s)) => s,
+            (true, None) => match self.web3().eth().gas_price().wait() {
+                Ok(s) => s,
+                Err(_e) => return Err(String::from("unable to fetch gas price")),
+            },
+        };
+
+        let gas_limit = match gas.limit {
+            Some(s) => s,
+            None => match estimate(self.address(), data, self.web3()) {
+                Ok(s) => s,
+                Err(e) => return Err(e),
+            },
+        };
+
+        Ok(Fees {
+            gas_price,
+            gas_limit,
+        })
     }
 
-    fn gas_limit(&self) -> U256 {
-        U256::from(250000u32)
+    // builds the relayed transaction, ready to be signed
+    fn prepare(&self, wallet: Address, data: ABIBytes) -> Result<Session, String> {
+        let nonce = match self.nonce(wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        let fees = match self.fees(&data) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        let hash = sign_hash::sign_hash(
+            self.address(),
+            wallet,
+            self.value(),
+            &data,
+            nonce,
+            fees.gas_price,
+            fees.gas_limit,
+        );
+
+        Ok(Session::new(
+            self.address(),
+            wallet,
+            Bytes(data),
+            nonce,
+            fees,
+            Bytes(hash.as_bytes().to_vec()),
+        ))
+    }
+}
+
+// gas needed by the inner call, which modules only accept from themselves, plus what execute
+// spends on top of it
+pub fn estimate<T: web3::Transport>(
+    module: Address,
+    data: &[u8],
+    web3: &Web3<T>,
+) -> Result<U256, String> {
+    let result = web3.eth().estimate_gas(
+        CallRequest {
+            from: Some(module),
+            to: module,
+            gas: None,
+            gas_price: None,
+            value: None,
+            data: Some(Bytes(data.to_vec())),
+        },
+        None,
+    );
+
+    match result.wait() {
+        Ok(s) => Ok(s + constants::RELAY_GAS_OVERHEAD),
+        Err(_e) => Err(format!(
+            "unable to estimate gas through {:?}: use --gas-limit",
+            module
+        )),
+    }
+}
+
+// next valid relayer nonce of a wallet for a module, built the way the Argent relayer does
+pub fn nonce<T: web3::Transport>(
+    module: Address,
+    wallet: Address,
+    web3: &Web3<T>,
+) -> Result<U256, String> {
+    let contract =
+        Contract::from_json(web3.eth(), module, constants::abis::RELAYER_MODULE).unwrap();
+
+    let result = contract.query("getNonce", (wallet,), None, Options::default(), None);
+    let current: U256 = match result.wait() {
+        Ok(s) => s,
+        Err(_e) => {
+            return Err(format!(
+                "unable to fetch nonce of {:?} for {:?}",
+                wallet, module
+            ))
+        }
+    };
+
+    let block = match web3.eth().block_number().wait() {
+        Ok(s) => s,
+        Err(_e) => return Err(String::from("unable to fetch last block")),
+    };
+
+    Ok(next_nonce(current, block.as_u64()))
+}
+
+// nonces are made of the block number in the upper 128 bits and a counter in the lower ones:
+// modules only accept nonces strictly greater than the last one used
+pub fn next_nonce(current: U256, block: u64) -> U256 {
+    let nonce = U256::from(block) << 128;
+
+    if nonce > current {
+        nonce
+    } else {
+        current + 1
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::next_nonce;
+    use web3::types::U256;
+
+    #[test]
+    fn test_next_nonce() {
+        let block = U256::from(9_000_000u64) << 128;
+
+        assert_eq!(next_nonce(U256::zero(), 9_000_000), block);
+        assert_eq!(next_nonce(block - 1, 9_000_000), block);
+        assert_eq!(next_nonce(block, 9_000_000), block + 1);
+        assert_eq!(next_nonce(block + 7, 9_000_000), block + 8);
+        assert_eq!(
+            next_nonce(block + 7, 9_000_001),
+            U256::from(9_000_001u64) << 128
+        );
     }
 }
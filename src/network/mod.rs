@@ -0,0 +1,355 @@
+use crate::constants;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::RwLock;
+use web3::types::Address;
+
+// addresses of the ENS registry, of the Argent modules (along with their names) and of the
+// tokens known on a network. Logs are scanned from its start block, the first one Argent was
+// deployed at, and its transactions are linked to on its explorer, if any
+#[derive(Clone, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Network {
+    pub name: String,
+    pub chain_id: u64,
+    #[serde(default)]
+    pub start_block: u64,
+    #[serde(default)]
+    pub ens: Option<Address>,
+    #[serde(default)]
+    pub explorer: Option<String>,
+    #[serde(default)]
+    pub modules: BTreeMap<Address, String>,
+    #[serde(default)]
+    pub tokens: Vec<Token>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Token {
+    pub symbol: String,
+    pub address: Address,
+    pub decimals: u32,
+}
+
+lazy_static! {
+    static ref CURRENT: RwLock<Network> = RwLock::new(mainnet());
+}
+
+impl Network {
+    // address of a module from its name, names being matched case-insensitively
+    pub fn module(&self, name: &str) -> Result<Address, String> {
+        let matches: Vec<&Address> = self
+            .modules
+            .iter()
+            .filter(|(_, module)| module.eq_ignore_ascii_case(name))
+            .map(|(address, _)| address)
+            .collect();
+
+        match matches.as_slice() {
+            [address] => Ok(**address),
+            [] => Err(format!(
+                "unknown module {} on {}: add it to ~/{}",
+                name,
+                self.name,
+                constants::NETWORKS_FILE
+            )),
+            _ => Err(format!(
+                "ambiguous module name {}: use its address instead",
+                name
+            )),
+        }
+    }
+
+    pub fn module_name(&self, address: &Address) -> Option<&str> {
+        self.modules.get(address).map(|name| name.as_str())
+    }
+
+    pub fn token(&self, symbol: &str) -> Option<&Token> {
+        self.tokens
+            .iter()
+            .find(|token| token.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    pub fn ens(&self) -> Result<Address, String> {
+        match self.ens {
+            Some(s) => Ok(s),
+            None => Err(format!("no ENS registry on {}", self.name)),
+        }
+    }
+
+    // entries of a user defined network with the same name override the built-in ones
+    fn extend(&mut self, other: Network) {
+        self.chain_id = other.chain_id;
+        if other.start_block != 0 {
+            self.start_block = other.start_block;
+        }
+        if other.ens.is_some() {
+            self.ens = other.ens;
+        }
+        if other.explorer.is_some() {
+            self.explorer = other.explorer;
+        }
+        self.modules.extend(other.modules);
+        for token in other.tokens {
+            self.tokens
+                .retain(|known| !known.symbol.eq_ignore_ascii_case(&token.symbol));
+            self.tokens.push(token);
+        }
+    }
+}
+
+// the network commands run against, mainnet until another one is selected
+pub fn current() -> Network {
+    CURRENT.read().unwrap().clone()
+}
+
+pub fn select(network: Network) {
+    *CURRENT.write().unwrap() = network;
+}
+
+// the built-in networks, extended with the ones of ~/.argent/networks.json
+pub fn load() -> Result<Vec<Network>, String> {
+    let networks = vec![mainnet(), ropsten(), goerli(), devnet()];
+
+    let path = match path() {
+        Ok(s) => s,
+        Err(e) => return Err(e),
+    };
+
+    if !path.exists() {
+        return Ok(networks);
+    }
+
+    let json = match fs::read_to_string(&path) {
+        Ok(s) => s,
+        Err(_e) => return Err(format!("unable to read networks {:?}", path)),
+    };
+
+    let defined: Vec<Network> = match serde_json::from_str(&json) {
+        Ok(s) => s,
+        Err(e) => return Err(format!("invalid networks {:?}: {}", path, e)),
+    };
+
+    Ok(merge(networks, defined))
+}
+
+// the network named so, or the one of a chain id
+pub fn find(networks: &[Network], name: Option<&str>, chain_id: u64) -> Result<Network, String> {
+    let found = match name {
+        Some(name) => networks
+            .iter()
+            .find(|network| network.name.eq_ignore_ascii_case(name)),
+        None => networks.iter().find(|network| network.chain_id == chain_id),
+    };
+
+    match (found, name) {
+        (Some(s), _) => Ok(s.clone()),
+        (None, Some(name)) => Err(format!("unknown network {}", name)),
+        (None, None) => Err(format!(
+            "unknown network with chain id {}: add it to ~/{}",
+            chain_id,
+            constants::NETWORKS_FILE
+        )),
+    }
+}
+
+fn merge(mut networks: Vec<Network>, defined: Vec<Network>) -> Vec<Network> {
+    for network in defined {
+        match networks
+            .iter_mut()
+            .find(|known| known.name.eq_ignore_ascii_case(&network.name))
+        {
+            Some(known) => known.extend(network),
+            None => networks.push(network),
+        }
+    }
+
+    networks
+}
+
+fn mainnet() -> Network {
+    let address = |address: &str| Address::from_str(address).unwrap();
+
+    let mut modules = BTreeMap::new();
+    for (module, name) in &[
+        (
+            "FF5A7299ff6f0fbAad9b38906b77d08c0FBdc9A7",
+            "GuardianManager",
+        ),
+        ("0BC693480d447AB97AfF7aa215D1586f1868Cb01", "LockManager"),
+        (
+            "dfa1468D07Fc86840A6EB53E0e65CEBDE81D1af9",
+            "RecoveryManager",
+        ),
+        ("ed0DA07AAB7257Df53Efc4DfC076745744138Ed9", "TokenExchanger"),
+        ("1848e646Bba45174f4044443719Db6E5E6Cf5D66", "NftTransfer"),
+        ("963F86DA34Cf2CE619d4B8e5cE96577943f95B6b", "MakerManager"),
+        (
+            "A5d7d68D7975e89FEb240f42feD1D77bb71b1cAF",
+            "CompoundManager",
+        ),
+        ("5388b0f8106BDE37DC6982b4Ba5771d2E8D9dc42", "UniswapManager"),
+        (
+            "2B6D87F12B106E1D3fA7137494751566329d1045",
+            "TransferManager",
+        ),
+        (
+            "cd23f51912ea8Fff38815f628277731C25c7Fb02",
+            "LegacyApprovedTransfer",
+        ),
+        ("7557f4199aa99e5396330BaC3b7bDAa262CB1913", "MakerV2Manager"),
+        (
+            "0045684552109f8551CC5c8aa7B1f52085adFf47",
+            "ApprovedTransfer",
+        ),
+        ("4DD68a6C27359E5640Fa6dCAF13631398C5613f1", "ModuleManager"),
+        ("df6767A7715381867738cF211290F61697ecd938", "TokenTransfer"),
+    ] {
+        modules.insert(address(module), name.to_string());
+    }
+
+    let tokens = [
+        ("ANT", "960b236A07cf122663c4303350609A66A7B288C0", 18),
+        ("DAI", "6B175474E89094C44Da98b954EedeAC495271d0F", 18),
+        ("SAI", "89d24a6b4ccb1b6faa2625fe562bdd9a23260359", 18),
+        ("WBTC", "2260fac5e5542a773aa44fbcfedf7c193bc2c599", 8),
+    ]
+    .iter()
+    .map(|(symbol, token, decimals)| Token {
+        symbol: symbol.to_string(),
+        address: address(token),
+        decimals: *decimals,
+    })
+    .collect();
+
+    Network {
+        name: String::from("mainnet"),
+        chain_id: 1,
+        start_block: 7_173_577,
+        ens: Some(address("314159265dD8dbb310642f98f50C066173C1259b")),
+        explorer: Some(String::from("https://etherscan.io")),
+        modules,
+        tokens,
+    }
+}
+
+// the Argent deployments of ropsten and goerli are not bundled yet: until they are, their modules
+// and tokens come from the networks file, as those of devnets do
+fn ropsten() -> Network {
+    Network {
+        name: String::from("ropsten"),
+        chain_id: 3,
+        start_block: 0,
+        ens: Some(Address::from_str("00000000000C2E074eC69A0dFb2997BA6C7d2e1e").unwrap()),
+        explorer: Some(String::from("https://ropsten.etherscan.io")),
+        modules: BTreeMap::new(),
+        tokens: Vec::new(),
+    }
+}
+
+fn goerli() -> Network {
+    Network {
+        name: String::from("goerli"),
+        chain_id: 5,
+        start_block: 0,
+        ens: Some(Address::from_str("00000000000C2E074eC69A0dFb2997BA6C7d2e1e").unwrap()),
+        explorer: Some(String::from("https://goerli.etherscan.io")),
+        modules: BTreeMap::new(),
+        tokens: Vec::new(),
+    }
+}
+
+fn devnet() -> Network {
+    Network {
+        name: String::from("devnet"),
+        chain_id: 1337,
+        start_block: 0,
+        ens: None,
+        explorer: None,
+        modules: BTreeMap::new(),
+        tokens: Vec::new(),
+    }
+}
+
+fn path() -> Result<PathBuf, String> {
+    let home = match env::var("HOME") {
+        Ok(s) => s,
+        Err(_e) => return Err(String::from("unable to locate home directory")),
+    };
+
+    let mut path = PathBuf::from(home);
+    path.push(constants::NETWORKS_FILE);
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{devnet, find, mainnet, merge, Network};
+
+    const DEFINED: &str = r#"[
+        {
+            "name": "devnet",
+            "chainId": 31337,
+            "startBlock": 42,
+            "explorer": "http://localhost:4000",
+            "modules": {
+                "0x1000000000000000000000000000000000000001": "GuardianManager",
+                "0x1000000000000000000000000000000000000002": "RecoveryManager"
+            },
+            "tokens": [
+                {
+                    "symbol": "DAI",
+                    "address": "0x1000000000000000000000000000000000000003",
+                    "decimals": 18
+                }
+            ]
+        },
+        { "name": "staging", "chainId": 4242 }
+    ]"#;
+
+    #[test]
+    fn test_mainnet() {
+        let mainnet = mainnet();
+
+        assert_eq!(
+            format!("{:x}", mainnet.module("recoverymanager").unwrap()),
+            "dfa1468d07fc86840a6eb53e0e65cebde81d1af9"
+        );
+        assert_eq!(
+            format!("{:x}", mainnet.module("ApprovedTransfer").unwrap()),
+            "0045684552109f8551cc5c8aa7b1f52085adff47"
+        );
+        assert!(mainnet.module("Unknown").is_err());
+        assert_eq!(mainnet.token("wbtc").unwrap().decimals, 8);
+        assert!(devnet().module("GuardianManager").is_err());
+    }
+
+    #[test]
+    fn test_merge() {
+        let defined: Vec<Network> = serde_json::from_str(DEFINED).unwrap();
+        let networks = merge(vec![mainnet(), devnet()], defined);
+
+        assert_eq!(networks.len(), 3);
+
+        let devnet = find(&networks, None, 31337).unwrap();
+        assert_eq!(devnet.name, "devnet");
+        assert!(devnet.module("GuardianManager").is_ok());
+        assert!(devnet.module("LockManager").is_err());
+        assert!(devnet.token("DAI").is_some());
+        assert!(devnet.ens().is_err());
+        assert_eq!(devnet.start_block, 42);
+        assert_eq!(devnet.explorer.unwrap(), "http://localhost:4000");
+        assert_eq!(find(&networks, None, 1).unwrap().start_block, 7_173_577);
+
+        assert_eq!(find(&networks, Some("Staging"), 1).unwrap().chain_id, 4242);
+        assert_eq!(find(&networks, None, 1).unwrap().name, "mainnet");
+        assert!(find(&networks, Some("kovan"), 1).is_err());
+        assert!(find(&networks, None, 42).is_err());
+    }
+}
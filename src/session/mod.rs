@@ -0,0 +1,248 @@
+use crate::constants;
+use crate::modules::Fees;
+use crate::network;
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use web3::types::{Address, Bytes, H520, U256};
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Signature {
+    pub signer: Address,
+    pub signature: Bytes,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Session {
+    pub module: Address,
+    pub wallet: Address,
+    pub data: Bytes,
+    pub nonce: U256,
+    pub fees: Fees,
+    pub hash: Bytes,
+    pub signatures: Vec<Signature>,
+}
+
+impl Session {
+    pub fn new(
+        module: Address,
+        wallet: Address,
+        data: Bytes,
+        nonce: U256,
+        fees: Fees,
+        hash: Bytes,
+    ) -> Self {
+        Session {
+            module,
+            wallet,
+            data,
+            nonce,
+            fees,
+            hash,
+            signatures: Vec::new(),
+        }
+    }
+
+    pub fn load(module: Address, wallet: Address) -> Result<Option<Session>, String> {
+        let path = match path(module, wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let json = match fs::read_to_string(&path) {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to read session {:?}", path)),
+        };
+
+        match serde_json::from_str(&json) {
+            Ok(s) => Ok(Some(s)),
+            Err(_e) => Err(format!("invalid session {:?}", path)),
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = match path(self.module, self.wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        if let Some(dir) = path.parent() {
+            if fs::create_dir_all(dir).is_err() {
+                return Err(format!("unable to create sessions directory {:?}", dir));
+            }
+        }
+
+        let json = serde_json::to_string_pretty(self).unwrap();
+
+        match fs::write(&path, json) {
+            Ok(_) => Ok(()),
+            Err(_e) => Err(format!("unable to write session {:?}", path)),
+        }
+    }
+
+    pub fn discard(&self) -> Result<(), String> {
+        let path = match path(self.module, self.wallet) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        if !path.exists() {
+            return Ok(());
+        }
+
+        match fs::remove_file(&path) {
+            Ok(_) => Ok(()),
+            Err(_e) => Err(format!("unable to remove session {:?}", path)),
+        }
+    }
+
+    pub fn add(&mut self, signer: Address, signature: H520) -> Result<(), String> {
+        if self.signers().contains(&signer) {
+            return Err(format!("{:?} has already signed", signer));
+        }
+
+        self.signatures.push(Signature {
+            signer,
+            signature: Bytes(signature.as_bytes().to_vec()),
+        });
+
+        Ok(())
+    }
+
+    pub fn signers(&self) -> Vec<Address> {
+        self.signatures.iter().map(|s| s.signer).collect()
+    }
+
+    // modules expect the owner signature first, if any, then the guardians signatures sorted by
+    // ascending signer address
+    pub fn signatures(&self, owner: Address) -> Vec<u8> {
+        let mut signatures = self.signatures.clone();
+        signatures.sort_by_key(|s| (s.signer != owner, s.signer));
+
+        let mut concatenated = Vec::<u8>::new();
+        for signature in signatures.iter() {
+            concatenated.extend_from_slice(&signature.signature.0);
+        }
+
+        concatenated
+    }
+}
+
+// checks signers are in the order modules expect, as signatures puts them in
+pub fn ordered(signers: &[Address], owner: Address) -> Result<(), String> {
+    for (index, signer) in signers.iter().enumerate() {
+        if *signer == owner && index > 0 {
+            return Err(String::from("the owner signature must come first"));
+        }
+
+        if index == 0 || signers[index - 1] == owner {
+            continue;
+        }
+
+        let previous = signers[index - 1];
+        if previous == *signer {
+            return Err(format!("{:?} signed twice", signer));
+        }
+        if previous > *signer {
+            return Err(format!("{:?} must come before {:?}", signer, previous));
+        }
+    }
+
+    Ok(())
+}
+
+// sessions are kept per chain: the same module and wallet addresses may exist on several of them
+fn path(module: Address, wallet: Address) -> Result<PathBuf, String> {
+    let home = match env::var("HOME") {
+        Ok(s) => s,
+        Err(_e) => return Err(String::from("unable to locate home directory")),
+    };
+
+    let mut path = PathBuf::from(home);
+    path.push(constants::SESSIONS_DIR);
+    path.push(format!(
+        "{}-{:x}-{:x}.json",
+        network::current().chain_id,
+        module,
+        wallet
+    ));
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ordered, Session};
+    use crate::modules::Fees;
+    use std::str::FromStr;
+    use web3::types::{Address, Bytes, H520, U256};
+
+    #[test]
+    fn test_signatures_are_sorted_by_signer() {
+        let mut session = Session::new(
+            Address::zero(),
+            Address::zero(),
+            Bytes(vec![]),
+            U256::zero(),
+            Fees {
+                gas_price: U256::zero(),
+                gas_limit: U256::zero(),
+            },
+            Bytes(vec![]),
+        );
+        let high = Address::from_str("ff00000000000000000000000000000000000000").unwrap();
+        let low = Address::from_str("0100000000000000000000000000000000000000").unwrap();
+
+        session.add(high, H520::repeat_byte(2)).unwrap();
+        session.add(low, H520::repeat_byte(1)).unwrap();
+
+        let signatures = session.signatures(Address::zero());
+        assert_eq!(signatures.len(), 130);
+        assert_eq!(&signatures[..65], H520::repeat_byte(1).as_bytes());
+        assert_eq!(&signatures[65..], H520::repeat_byte(2).as_bytes());
+        assert!(session.add(low, H520::repeat_byte(1)).is_err());
+    }
+
+    #[test]
+    fn test_owner_signature_comes_first() {
+        let mut session = Session::new(
+            Address::zero(),
+            Address::zero(),
+            Bytes(vec![]),
+            U256::zero(),
+            Fees {
+                gas_price: U256::zero(),
+                gas_limit: U256::zero(),
+            },
+            Bytes(vec![]),
+        );
+        let owner = Address::from_str("ff00000000000000000000000000000000000000").unwrap();
+        let guardian = Address::from_str("0100000000000000000000000000000000000000").unwrap();
+
+        session.add(guardian, H520::repeat_byte(1)).unwrap();
+        session.add(owner, H520::repeat_byte(2)).unwrap();
+
+        let signatures = session.signatures(owner);
+        assert_eq!(&signatures[..65], H520::repeat_byte(2).as_bytes());
+        assert_eq!(&signatures[65..], H520::repeat_byte(1).as_bytes());
+    }
+
+    #[test]
+    fn test_ordered() {
+        let owner = Address::from_str("ff00000000000000000000000000000000000000").unwrap();
+        let high = Address::from_str("0200000000000000000000000000000000000000").unwrap();
+        let low = Address::from_str("0100000000000000000000000000000000000000").unwrap();
+
+        assert!(ordered(&[owner, low, high], owner).is_ok());
+        assert!(ordered(&[low, high], owner).is_ok());
+        assert!(ordered(&[], owner).is_ok());
+        assert!(ordered(&[low, owner], owner).is_err());
+        assert!(ordered(&[owner, high, low], owner).is_err());
+        assert!(ordered(&[low, low], owner).is_err());
+    }
+}
@@ -0,0 +1,116 @@
+use tiny_keccak::{Hasher, Keccak};
+use web3::types::{Address, H256, U256};
+
+const ETH_SIGN_PREFIX: &[u8] = b"\x19Ethereum Signed Message:\n32";
+
+// ERC-1077 hash of a relayed transaction, as computed by the modules' getSignHash
+pub fn sign_hash(
+    from: Address,
+    to: Address,
+    value: U256,
+    data: &[u8],
+    nonce: U256,
+    gas_price: U256,
+    gas_limit: U256,
+) -> H256 {
+    let value: [u8; 32] = value.into();
+    let nonce: [u8; 32] = nonce.into();
+    let gas_price: [u8; 32] = gas_price.into();
+    let gas_limit: [u8; 32] = gas_limit.into();
+
+    let mut packed = Vec::<u8>::new();
+    packed.extend_from_slice(b"\x19\x00");
+    packed.extend_from_slice(from.as_bytes());
+    packed.extend_from_slice(to.as_bytes());
+    packed.extend_from_slice(&value);
+    packed.extend_from_slice(data);
+    packed.extend_from_slice(&nonce);
+    packed.extend_from_slice(&gas_price);
+    packed.extend_from_slice(&gas_limit);
+
+    keccak256(&packed)
+}
+
+// a hash prefixed the way eth_sign does, i.e. what the modules actually recover signers from
+pub fn prefix(hash: H256) -> H256 {
+    let mut packed = Vec::<u8>::new();
+    packed.extend_from_slice(ETH_SIGN_PREFIX);
+    packed.extend_from_slice(hash.as_bytes());
+
+    keccak256(&packed)
+}
+
+pub fn keccak256(data: &[u8]) -> H256 {
+    let mut keccak = Keccak::v256();
+    let mut hash = [0u8; 32];
+    keccak.update(data);
+    keccak.finalize(&mut hash);
+
+    H256::from(hash)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{keccak256, prefix, sign_hash};
+    use std::str::FromStr;
+    use web3::types::{Address, H256, U256};
+
+    #[test]
+    fn test_keccak256() {
+        assert_eq!(
+            keccak256(&[]),
+            H256::from_str("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_sign_hash() {
+        let module = Address::from_str("dfa1468d07fc86840a6eb53e0e65cebde81d1af9").unwrap();
+        let wallet = Address::from_str("b1e1c7d9b2a6e4fb9b5bd0e7d6e8c0a1d3f5e7a9").unwrap();
+        // executeRecovery(wallet, 0x000000000000000000000000000000000000dead)
+        let data = hex::decode(
+            "b0ba4da0\
+             000000000000000000000000b1e1c7d9b2a6e4fb9b5bd0e7d6e8c0a1d3f5e7a9\
+             000000000000000000000000000000000000000000000000000000000000dead",
+        )
+        .unwrap();
+
+        // keccak256(abi.encodePacked(byte(0x19), byte(0), from, to, value, data, nonce, gasPrice,
+        // gasLimit)) as RelayerModule.getSignHash packs it, and its eth_sign prefixed hash, both
+        // computed apart from this crate with a reference Keccak-256 implementation
+        let vectors = vec![
+            (
+                data,
+                U256::from(42),
+                U256::zero(),
+                U256::from(250_000),
+                "e97f935d564e920fd1269a4250a0e61e543cec35eba23a3e674ce5375c7b9e4a",
+                "4631aed359f488a28e83814178021a8d7986c29c2fd10238b9e5efd2e7757fd8",
+            ),
+            (
+                vec![],
+                U256::from(1),
+                U256::from(1_000_000_000),
+                U256::from(100_000),
+                "c3acf4664c3f443a8fc3318317f12a71f5e5797c0180f6370c984b91441ff0d7",
+                "f1a4e4dfc4b4483a236e79f36833586e7a722b9618b64895a9dcaa458c6c934f",
+            ),
+        ];
+
+        for (data, nonce, gas_price, gas_limit, hash, prefixed) in vectors {
+            let computed = sign_hash(
+                module,
+                wallet,
+                U256::zero(),
+                &data,
+                nonce,
+                gas_price,
+                gas_limit,
+            );
+
+            assert_eq!(computed, H256::from_str(hash).unwrap());
+            assert_eq!(prefix(computed), H256::from_str(prefixed).unwrap());
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use crate::sign_hash;
+use secp256k1::recovery::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+use std::fmt;
+use web3::types::{Address, H256, H520};
+
+// a private key held locally, whatever it has been loaded from
+#[derive(Clone)]
+pub struct Key {
+    secret: SecretKey,
+}
+
+impl Key {
+    pub fn from_secret(secret: &[u8]) -> Result<Self, String> {
+        match SecretKey::from_slice(secret) {
+            Ok(s) => Ok(Key { secret: s }),
+            Err(_e) => Err(String::from("invalid private key")),
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        let secp = Secp256k1::signing_only();
+
+        address(&PublicKey::from_secret_key(&secp, &self.secret))
+    }
+
+    // signature of a hash as eth_sign returns it, i.e. r, s and v as 27 or 28
+    pub fn sign(&self, hash: H256) -> H520 {
+        let (recovery, signature) = self.sign_recoverable(hash);
+
+        let mut bytes = [0u8; 65];
+        bytes[..64].copy_from_slice(&signature);
+        bytes[64] = recovery as u8 + 27;
+
+        H520::from(bytes)
+    }
+
+    // eth_sign of a message, i.e. the signature of its prefixed keccak
+    pub fn sign_message(&self, message: &[u8]) -> H520 {
+        let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+        prefixed.extend_from_slice(message);
+
+        self.sign(sign_hash::keccak256(&prefixed))
+    }
+
+    pub fn sign_recoverable(&self, hash: H256) -> (i32, [u8; 64]) {
+        let secp = Secp256k1::signing_only();
+        let message = Message::from_slice(hash.as_bytes()).unwrap();
+        let (recovery, signature) = secp
+            .sign_recoverable(&message, &self.secret)
+            .serialize_compact();
+
+        (RecoveryId::to_i32(recovery), signature)
+    }
+}
+
+// ecrecover of a 65 bytes signature, v being either 27 or 28 as eth_sign returns it or the bare
+// recovery id
+pub fn recover(hash: H256, signature: &[u8]) -> Result<Address, String> {
+    if signature.len() != 65 {
+        return Err(format!("invalid signature length {}", signature.len()));
+    }
+
+    let v = signature[64];
+    let recovery = match RecoveryId::from_i32(i32::from(if v >= 27 { v - 27 } else { v })) {
+        Ok(s) => s,
+        Err(_e) => return Err(format!("invalid signature recovery id {}", v)),
+    };
+
+    let signature = match RecoverableSignature::from_compact(&signature[..64], recovery) {
+        Ok(s) => s,
+        Err(_e) => return Err(String::from("invalid signature")),
+    };
+
+    let secp = Secp256k1::verification_only();
+    let message = Message::from_slice(hash.as_bytes()).unwrap();
+
+    match secp.recover(&message, &signature) {
+        Ok(s) => Ok(address(&s)),
+        Err(_e) => Err(String::from("unable to recover signer")),
+    }
+}
+
+fn address(public: &PublicKey) -> Address {
+    let public = public.serialize_uncompressed();
+    let hash = sign_hash::keccak256(&public[1..]);
+
+    Address::from_slice(&hash.as_bytes()[12..])
+}
+
+// never print the secret itself
+impl fmt::Debug for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Key({:?})", self.address())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{recover, Key};
+    use crate::sign_hash;
+    use std::str::FromStr;
+    use web3::types::Address;
+
+    #[test]
+    fn test_address() {
+        let key = Key::from_secret(&[0x46; 32]).unwrap();
+
+        assert_eq!(
+            key.address(),
+            Address::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap()
+        );
+        assert!(Key::from_secret(&[0u8; 32]).is_err());
+        assert!(format!("{:?}", key).contains("9d8a62f6"));
+    }
+
+    #[test]
+    fn test_recover() {
+        let key = Key::from_secret(&[0x46; 32]).unwrap();
+        let hash = sign_hash::keccak256(b"argent");
+        let mut signature = key.sign(hash).as_bytes().to_vec();
+
+        assert_eq!(recover(hash, &signature).unwrap(), key.address());
+
+        signature[64] -= 27;
+        assert_eq!(recover(hash, &signature).unwrap(), key.address());
+
+        assert_ne!(
+            recover(sign_hash::keccak256(b"wallet"), &signature).unwrap(),
+            key.address()
+        );
+        assert!(recover(hash, &signature[..64]).is_err());
+    }
+}
@@ -0,0 +1,237 @@
+use crate::sign_hash;
+use crate::signer::Key;
+use aes::Aes128;
+use ctr::cipher::{NewCipher, StreamCipher};
+use ctr::Ctr128BE;
+use hmac::Hmac;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::fs;
+
+// Web3 Secret Storage v3 keystore, as geth, parity or MyCrypto write them
+#[derive(Deserialize)]
+struct Keystore {
+    version: u32,
+    #[serde(alias = "Crypto")]
+    crypto: Crypto,
+}
+
+#[derive(Deserialize)]
+struct Crypto {
+    cipher: String,
+    cipherparams: CipherParams,
+    ciphertext: String,
+    kdf: String,
+    kdfparams: KdfParams,
+    mac: String,
+}
+
+#[derive(Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u64,
+        r: u32,
+        p: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        c: usize,
+        dklen: usize,
+        prf: String,
+        salt: String,
+    },
+}
+
+pub fn load(path: &str, password: &str) -> Result<Key, String> {
+    match fs::read_to_string(path) {
+        Ok(s) => decrypt(&s, password),
+        Err(_e) => Err(format!("unable to read keystore {}", path)),
+    }
+}
+
+pub fn decrypt(json: &str, password: &str) -> Result<Key, String> {
+    let keystore: Keystore = match serde_json::from_str(json) {
+        Ok(s) => s,
+        Err(_e) => return Err(String::from("invalid keystore")),
+    };
+
+    if keystore.version != 3 {
+        return Err(format!("unsupported keystore version {}", keystore.version));
+    }
+
+    let crypto = keystore.crypto;
+
+    if crypto.cipher != "aes-128-ctr" {
+        return Err(format!("unsupported keystore cipher {}", crypto.cipher));
+    }
+
+    let derived = match derive(&crypto.kdf, &crypto.kdfparams, password.as_bytes()) {
+        Ok(s) => s,
+        Err(e) => return Err(e),
+    };
+
+    if derived.len() < 32 {
+        return Err(String::from("invalid keystore derived key length"));
+    }
+
+    let (ciphertext, iv, mac) = match (
+        hex::decode(&crypto.ciphertext),
+        hex::decode(&crypto.cipherparams.iv),
+        hex::decode(&crypto.mac),
+    ) {
+        (Ok(ciphertext), Ok(iv), Ok(mac)) => (ciphertext, iv, mac),
+        _ => return Err(String::from("invalid keystore encoding")),
+    };
+
+    let mut payload = derived[16..32].to_vec();
+    payload.extend_from_slice(&ciphertext);
+
+    if sign_hash::keccak256(&payload).as_bytes() != mac.as_slice() {
+        return Err(String::from("wrong keystore password"));
+    }
+
+    if iv.len() != 16 {
+        return Err(String::from("invalid keystore iv"));
+    }
+
+    let mut secret = ciphertext;
+    Ctr128BE::<Aes128>::new(derived[..16].into(), iv.as_slice().into())
+        .apply_keystream(&mut secret);
+
+    Key::from_secret(&secret)
+}
+
+fn derive(kdf: &str, params: &KdfParams, password: &[u8]) -> Result<Vec<u8>, String> {
+    match (kdf, params) {
+        (
+            "scrypt",
+            KdfParams::Scrypt {
+                dklen,
+                n,
+                r,
+                p,
+                salt,
+            },
+        ) => {
+            let salt = match hex::decode(salt) {
+                Ok(s) => s,
+                Err(_e) => return Err(String::from("invalid keystore salt")),
+            };
+
+            if !n.is_power_of_two() {
+                return Err(format!("invalid scrypt cost {}", n));
+            }
+
+            let params = match scrypt::ScryptParams::new(n.trailing_zeros() as u8, *r, *p) {
+                Ok(s) => s,
+                Err(_e) => return Err(String::from("invalid scrypt parameters")),
+            };
+
+            let mut derived = vec![0u8; *dklen];
+            match scrypt::scrypt(password, &salt, &params, &mut derived) {
+                Ok(_) => Ok(derived),
+                Err(_e) => Err(String::from("invalid scrypt derived key length")),
+            }
+        }
+        (
+            "pbkdf2",
+            KdfParams::Pbkdf2 {
+                c,
+                dklen,
+                prf,
+                salt,
+            },
+        ) => {
+            if prf != "hmac-sha256" {
+                return Err(format!("unsupported pbkdf2 function {}", prf));
+            }
+
+            let salt = match hex::decode(salt) {
+                Ok(s) => s,
+                Err(_e) => return Err(String::from("invalid keystore salt")),
+            };
+
+            let mut derived = vec![0u8; *dklen];
+            pbkdf2::pbkdf2::<Hmac<Sha256>>(password, &salt, *c, &mut derived);
+
+            Ok(derived)
+        }
+        _ => Err(format!("unsupported keystore kdf {}", kdf)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::decrypt;
+    use std::str::FromStr;
+    use web3::types::Address;
+
+    // the key being 7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d, the pbkdf2
+    // vector comes from the Web3 Secret Storage specification while the scrypt one uses cheap
+    // parameters geth would accept
+    const PBKDF2: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "6087dab2f9fdbbfaddc31a909735c1e6" },
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    const SCRYPT: &str = r#"{
+        "Crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": { "iv": "83dbcc02d8ccb40e466191a123791e0e" },
+            "ciphertext": "01a05c7f05b697274227d8bd0825a6caa89967e24643426c0fcfa2fb663052d7",
+            "kdf": "scrypt",
+            "kdfparams": {
+                "dklen": 32,
+                "n": 1024,
+                "p": 1,
+                "r": 8,
+                "salt": "ab0c7876052600dd703518d6fc3fe8984592145b591fc8fb5c6d43190334ba19"
+            },
+            "mac": "d60a6540bbdeaa746e4c7b4359c74e4bb0b679bedce5b4d129ad96150d200274"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    #[test]
+    fn test_decrypt_pbkdf2() {
+        let key = decrypt(PBKDF2, "testpassword").unwrap();
+
+        assert_eq!(
+            key.address(),
+            Address::from_str("008aeeda4d805471df9b2a5b0f38a0c3bcba786b").unwrap()
+        );
+        assert!(decrypt(PBKDF2, "wrongpassword").is_err());
+    }
+
+    #[test]
+    fn test_decrypt_scrypt() {
+        let key = decrypt(SCRYPT, "testpassword").unwrap();
+
+        assert_eq!(
+            key.address(),
+            Address::from_str("008aeeda4d805471df9b2a5b0f38a0c3bcba786b").unwrap()
+        );
+        assert!(decrypt(&SCRYPT.replace("1024", "1000"), "testpassword").is_err());
+    }
+}
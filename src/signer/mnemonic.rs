@@ -0,0 +1,149 @@
+use crate::signer::Key;
+use bip39::{Language, Mnemonic, Seed};
+use hmac::{Hmac, Mac};
+use secp256k1::{PublicKey, Secp256k1, SecretKey};
+use sha2::Sha512;
+
+pub const DEFAULT_PATH: &str = "m/44'/60'/0'/0/0";
+
+const HARDENED: u32 = 0x8000_0000;
+
+// BIP-39 seed of an english mnemonic, without passphrase
+pub fn seed(phrase: &str) -> Result<Vec<u8>, String> {
+    let phrase = phrase.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    match Mnemonic::from_phrase(&phrase, Language::English) {
+        Ok(s) => Ok(Seed::new(&s, "").as_bytes().to_vec()),
+        Err(_e) => Err(String::from("invalid mnemonic")),
+    }
+}
+
+// BIP-32 derivation of the key at a path such as m/44'/60'/0'/0/0
+pub fn derive(seed: &[u8], path: &str) -> Result<Key, String> {
+    let indexes = match parse(path) {
+        Ok(s) => s,
+        Err(e) => return Err(e),
+    };
+
+    let (mut secret, mut chain_code) = hmac(b"Bitcoin seed", seed);
+    let secp = Secp256k1::signing_only();
+
+    for index in indexes {
+        let mut data = Vec::<u8>::new();
+        if index >= HARDENED {
+            data.push(0);
+            data.extend_from_slice(&secret[..]);
+        } else {
+            let key = SecretKey::from_slice(&secret).unwrap();
+            data.extend_from_slice(&PublicKey::from_secret_key(&secp, &key).serialize());
+        }
+        data.extend_from_slice(&index.to_be_bytes());
+
+        let (tweak, child_chain_code) = hmac(&chain_code, &data);
+
+        let mut key = SecretKey::from_slice(&secret).unwrap();
+        if key.add_assign(&tweak).is_err() {
+            return Err(format!("unable to derive {}", path));
+        }
+
+        secret.copy_from_slice(&key[..]);
+        chain_code = child_chain_code;
+    }
+
+    Key::from_secret(&secret)
+}
+
+// the same path with its last index replaced, to derive sibling accounts
+pub fn sibling(path: &str, index: u32) -> Result<String, String> {
+    match self::index(path) {
+        Ok(_) => Ok(format!("{}/{}", &path[..path.rfind('/').unwrap()], index)),
+        Err(e) => Err(e),
+    }
+}
+
+pub fn index(path: &str) -> Result<u32, String> {
+    match parse(path) {
+        Ok(s) => match s.last() {
+            Some(index) if *index < HARDENED => Ok(*index),
+            _ => Err(format!(
+                "derivation path {} does not end with a normal index",
+                path
+            )),
+        },
+        Err(e) => Err(e),
+    }
+}
+
+fn parse(path: &str) -> Result<Vec<u32>, String> {
+    let error = format!("invalid derivation path {}", path);
+    let mut components = path.split('/');
+
+    if components.next() != Some("m") {
+        return Err(error);
+    }
+
+    let mut indexes = Vec::<u32>::new();
+    for component in components {
+        let (index, hardened) = if component.ends_with('\'') || component.ends_with('h') {
+            (&component[..component.len() - 1], true)
+        } else {
+            (component, false)
+        };
+
+        let index = match index.parse::<u32>() {
+            Ok(s) if s < HARDENED => s,
+            _ => return Err(error),
+        };
+
+        indexes.push(if hardened { index + HARDENED } else { index });
+    }
+
+    Ok(indexes)
+}
+
+fn hmac(key: &[u8], data: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = Hmac::<Sha512>::new_varkey(key).unwrap();
+    mac.input(data);
+    let result = mac.result().code();
+
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&result[..32]);
+    right.copy_from_slice(&result[32..]);
+
+    (left, right)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{derive, index, seed, sibling, DEFAULT_PATH};
+    use std::str::FromStr;
+    use web3::types::Address;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+                          abandon abandon about";
+
+    #[test]
+    fn test_derive() {
+        let seed = seed(PHRASE).unwrap();
+
+        assert_eq!(
+            derive(&seed, DEFAULT_PATH).unwrap().address(),
+            Address::from_str("9858effd232b4033e47d90003d41ec34ecaeda94").unwrap()
+        );
+        assert_eq!(
+            derive(&seed, "m/44'/60'/0'/0/1").unwrap().address(),
+            Address::from_str("6fac4d18c912343bf86fa7049364dd4e424ab9c0").unwrap()
+        );
+        assert!(derive(&seed, "44'/60'/0'/0/0").is_err());
+        assert!(super::seed("abandon abandon abandon").is_err());
+    }
+
+    #[test]
+    fn test_sibling() {
+        assert_eq!(sibling(DEFAULT_PATH, 7).unwrap(), "m/44'/60'/0'/0/7");
+        assert_eq!(index("m/44'/60'/0'/0/3").unwrap(), 3);
+        assert!(sibling("m/44'/60'/0'", 1).is_err());
+        assert!(index("m/44'/60'/0'").is_err());
+    }
+}
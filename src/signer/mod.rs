@@ -0,0 +1,310 @@
+mod key;
+pub mod keystore;
+pub mod mnemonic;
+mod transaction;
+
+pub use key::{recover, Key};
+pub use transaction::Transaction;
+
+use crate::helpers;
+use crate::network;
+use crate::sign_hash;
+use crate::tui;
+use std::cell::Cell;
+use std::fmt;
+use web3::api::Web3;
+use web3::futures::Future;
+use web3::types::{Address, BlockNumber, Bytes, CallRequest, TransactionRequest, H256, H520, U256};
+
+// accounts picked on the command line: the local key, the account signing for the wallet and
+// the one sending relayed transactions
+#[derive(Clone, Debug, Default)]
+pub struct Accounts {
+    pub key: Option<Key>,
+    pub from: Option<Address>,
+    pub relayer: Option<Address>,
+    pub offline: Option<Offline>,
+}
+
+// what the node would otherwise fill in, supplied on the command line to sign offline
+#[derive(Clone, Debug)]
+pub struct Offline {
+    pub chain_id: u64,
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas: U256,
+}
+
+// an account able to sign sign-hashes and to send transactions
+pub trait Signer: fmt::Debug {
+    fn address(&self) -> Address;
+
+    // eth_sign of a message, i.e. the signature of its prefixed keccak
+    fn sign(&self, message: &[u8]) -> Result<H520, String>;
+
+    fn send_transaction(&self, tx: TransactionRequest) -> Result<H256, String>;
+}
+
+// an account of the node, which signs and sends transactions on our behalf
+#[derive(Clone, Debug)]
+pub struct Node<'a, T: web3::Transport> {
+    address: Address,
+    web3: &'a Web3<T>,
+}
+
+impl<'a, T: web3::Transport> Node<'a, T> {
+    pub fn new(address: Address, web3: &'a Web3<T>) -> Self {
+        Node::<'a, T> { address, web3 }
+    }
+}
+
+impl<'a, T: web3::Transport> Signer for Node<'a, T> {
+    fn address(&self) -> Address {
+        self.address
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<H520, String> {
+        helpers::sign(self.address, Bytes(message.to_vec()), self.web3)
+    }
+
+    fn send_transaction(&self, tx: TransactionRequest) -> Result<H256, String> {
+        match self.web3.eth().send_transaction(tx).wait() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!(
+                "unable to send transaction from {:?}: {}",
+                self.address, e
+            )),
+        }
+    }
+}
+
+// a key held locally: the node only sees raw transactions
+#[derive(Clone, Debug)]
+pub struct Local<'a, T: web3::Transport> {
+    key: Key,
+    web3: &'a Web3<T>,
+}
+
+impl<'a, T: web3::Transport> Local<'a, T> {
+    pub fn new(key: Key, web3: &'a Web3<T>) -> Self {
+        Local::<'a, T> { key, web3 }
+    }
+}
+
+impl<'a, T: web3::Transport> Signer for Local<'a, T> {
+    fn address(&self) -> Address {
+        self.key.address()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<H520, String> {
+        Ok(self.key.sign_message(message))
+    }
+
+    // fills in whatever the node would have: nonce, gas price and gas limit
+    fn send_transaction(&self, tx: TransactionRequest) -> Result<H256, String> {
+        let eth = self.web3.eth();
+        let address = self.address();
+
+        let nonce = match tx.nonce {
+            Some(s) => s,
+            None => match eth
+                .transaction_count(address, Some(BlockNumber::Pending))
+                .wait()
+            {
+                Ok(s) => s,
+                Err(_e) => return Err(format!("unable to fetch nonce of {:?}", address)),
+            },
+        };
+
+        let gas_price = match tx.gas_price {
+            Some(s) => s,
+            None => match eth.gas_price().wait() {
+                Ok(s) => s,
+                Err(_e) => return Err(String::from("unable to fetch gas price")),
+            },
+        };
+
+        let to = match tx.to {
+            Some(s) => s,
+            None => return Err(String::from("contract creations are not supported")),
+        };
+
+        let gas = match tx.gas {
+            Some(s) => s,
+            None => {
+                let request = CallRequest {
+                    from: Some(address),
+                    to,
+                    gas: None,
+                    gas_price: Some(gas_price),
+                    value: tx.value,
+                    data: tx.data.clone(),
+                };
+
+                match eth.estimate_gas(request, None).wait() {
+                    Ok(s) => s,
+                    Err(e) => {
+                        return Err(format!(
+                            "unable to estimate gas: {}",
+                            helpers::revert_reason(&e)
+                        ))
+                    }
+                }
+            }
+        };
+
+        // signed for the selected network, as offline transactions and bundles are, which the
+        // node has to be on
+        let chain_id = network::current().chain_id;
+        match helpers::chain_id(self.web3) {
+            Ok(s) if s == chain_id => (),
+            Ok(s) => {
+                return Err(format!(
+                    "the node is on chain {} but the network is on chain {}",
+                    s, chain_id
+                ))
+            }
+            Err(e) => return Err(e),
+        };
+
+        let raw = Transaction {
+            nonce,
+            gas_price,
+            gas,
+            to: Some(to),
+            value: tx.value.unwrap_or_default(),
+            data: tx.data.map(|data| data.0).unwrap_or_default(),
+        }
+        .sign(&self.key, chain_id);
+
+        match eth.send_raw_transaction(raw).wait() {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!(
+                "unable to send transaction from {:?}: {}",
+                address, e
+            )),
+        }
+    }
+}
+
+// a key held locally whose transactions are printed instead of sent, to be broadcast later:
+// nothing is asked to the node, consecutive transactions taking consecutive nonces
+#[derive(Clone, Debug)]
+pub struct Raw {
+    key: Key,
+    offline: Offline,
+    nonce: Cell<U256>,
+}
+
+impl Raw {
+    pub fn new(key: Key, offline: Offline) -> Self {
+        let nonce = Cell::new(offline.nonce);
+
+        Raw {
+            key,
+            offline,
+            nonce,
+        }
+    }
+}
+
+impl Signer for Raw {
+    fn address(&self) -> Address {
+        self.key.address()
+    }
+
+    fn sign(&self, message: &[u8]) -> Result<H520, String> {
+        Ok(self.key.sign_message(message))
+    }
+
+    fn send_transaction(&self, tx: TransactionRequest) -> Result<H256, String> {
+        let to = match tx.to {
+            Some(s) => s,
+            None => return Err(String::from("contract creations are not supported")),
+        };
+
+        let nonce = tx.nonce.unwrap_or_else(|| self.nonce.get());
+
+        let raw = Transaction {
+            nonce,
+            gas_price: tx.gas_price.unwrap_or(self.offline.gas_price),
+            gas: tx.gas.unwrap_or(self.offline.gas),
+            to: Some(to),
+            value: tx.value.unwrap_or_default(),
+            data: tx.data.map(|data| data.0).unwrap_or_default(),
+        }
+        .sign(&self.key, self.offline.chain_id);
+
+        self.nonce.set(nonce + 1);
+
+        tui::header("raw transaction");
+        tui::info(format!("0x{}", hex::encode(&raw.0)));
+
+        Ok(sign_hash::keccak256(&raw.0))
+    }
+}
+
+// the signer of a given account: the local key if it is the one, the node otherwise
+pub fn of<'a, T: web3::Transport>(
+    address: Address,
+    accounts: &Accounts,
+    web3: &'a Web3<T>,
+) -> Box<dyn Signer + 'a> {
+    match (&accounts.key, &accounts.offline) {
+        (Some(key), Some(offline)) if key.address() == address => {
+            Box::new(Raw::new(key.clone(), offline.clone()))
+        }
+        (Some(key), None) if key.address() == address => Box::new(Local::new(key.clone(), web3)),
+        _ => Box::new(Node::new(address, web3)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Key, Offline, Raw, Signer};
+    use crate::sign_hash;
+    use std::str::FromStr;
+    use web3::types::{Address, TransactionRequest, U256};
+
+    // the transaction of the EIP-155 specification, signed without a node
+    #[test]
+    fn test_raw_transaction() {
+        let key = Key::from_secret(&[0x46; 32]).unwrap();
+        let signer = Raw::new(
+            key.clone(),
+            Offline {
+                chain_id: 1,
+                nonce: U256::from(9),
+                gas_price: U256::from(20_000_000_000u64),
+                gas: U256::from(21_000),
+            },
+        );
+        let request = TransactionRequest {
+            from: key.address(),
+            to: Some(Address::from_str("3535353535353535353535353535353535353535").unwrap()),
+            gas: None,
+            gas_price: None,
+            value: Some(U256::from(1_000_000_000_000_000_000u64)),
+            nonce: None,
+            data: None,
+            condition: None,
+        };
+
+        let raw = hex::decode(
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+             761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83",
+        )
+        .unwrap();
+
+        assert_eq!(
+            signer.send_transaction(request.clone()).unwrap(),
+            sign_hash::keccak256(&raw)
+        );
+        assert_ne!(
+            signer.send_transaction(request).unwrap(),
+            sign_hash::keccak256(&raw)
+        );
+        assert_eq!(signer.nonce.get(), U256::from(11));
+    }
+}
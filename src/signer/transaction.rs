@@ -0,0 +1,86 @@
+use crate::sign_hash;
+use crate::signer::Key;
+use rlp::RlpStream;
+use web3::types::{Address, Bytes, H256, U256};
+
+// a legacy transaction, signed locally the EIP-155 way
+#[derive(Clone, Debug)]
+pub struct Transaction {
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas: U256,
+    pub to: Option<Address>,
+    pub value: U256,
+    pub data: Vec<u8>,
+}
+
+impl Transaction {
+    pub fn hash(&self, chain_id: u64) -> H256 {
+        let mut stream = RlpStream::new_list(9);
+        self.append(&mut stream);
+        stream.append(&chain_id);
+        stream.append(&U256::zero());
+        stream.append(&U256::zero());
+
+        sign_hash::keccak256(&stream.out())
+    }
+
+    // the raw transaction, as eth_sendRawTransaction expects it
+    pub fn sign(&self, key: &Key, chain_id: u64) -> Bytes {
+        let (recovery, signature) = key.sign_recoverable(self.hash(chain_id));
+
+        let mut stream = RlpStream::new_list(9);
+        self.append(&mut stream);
+        stream.append(&(recovery as u64 + 35 + chain_id * 2));
+        stream.append(&U256::from(&signature[..32]));
+        stream.append(&U256::from(&signature[32..]));
+
+        Bytes(stream.out())
+    }
+
+    fn append(&self, stream: &mut RlpStream) {
+        stream.append(&self.nonce);
+        stream.append(&self.gas_price);
+        stream.append(&self.gas);
+        match self.to {
+            Some(to) => stream.append(&to),
+            None => stream.append_empty_data(),
+        };
+        stream.append(&self.value);
+        stream.append(&self.data);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Transaction;
+    use crate::signer::Key;
+    use std::str::FromStr;
+    use web3::types::{Address, H256, U256};
+
+    // example of the EIP-155 specification
+    #[test]
+    fn test_sign_transaction() {
+        let key = Key::from_secret(&[0x46; 32]).unwrap();
+        let transaction = Transaction {
+            nonce: U256::from(9),
+            gas_price: U256::from(20_000_000_000u64),
+            gas: U256::from(21_000),
+            to: Some(Address::from_str("3535353535353535353535353535353535353535").unwrap()),
+            value: U256::from(1_000_000_000_000_000_000u64),
+            data: vec![],
+        };
+
+        assert_eq!(
+            transaction.hash(1),
+            H256::from_str("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53")
+                .unwrap()
+        );
+        assert_eq!(
+            hex::encode(transaction.sign(&key, 1).0),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a7640000\
+             8025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f\
+             761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+}
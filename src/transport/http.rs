@@ -0,0 +1,147 @@
+use crate::config::Endpoint;
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper_tls::HttpsConnector;
+use jsonrpc_core as rpc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use web3::futures::future::{self, Either};
+use web3::futures::sync::{mpsc, oneshot};
+use web3::futures::{Future, Stream};
+use web3::transports::EventLoopHandle;
+use web3::{Error, RequestId};
+
+const MAX_PARALLEL: usize = 64;
+
+type Pending = oneshot::Sender<Result<hyper::Chunk, Error>>;
+
+// JSON-RPC over HTTP the way web3's own transport does it, plus the headers private nodes ask
+// for. The event loop is stopped once its handle is dropped
+#[derive(Clone, Debug)]
+pub struct Http {
+    id: Arc<AtomicUsize>,
+    url: hyper::Uri,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    sender: mpsc::UnboundedSender<(hyper::Request<hyper::Body>, Pending)>,
+}
+
+impl Http {
+    pub fn new(endpoint: &Endpoint) -> Result<(EventLoopHandle, Self), String> {
+        let url = match endpoint.url.parse::<hyper::Uri>() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("invalid ethereum endpoint {}", endpoint.url)),
+        };
+
+        let mut headers = Vec::new();
+        for (name, value) in endpoint.headers.iter() {
+            match (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                (Ok(name), Ok(value)) => headers.push((name, value)),
+                _ => return Err(format!("invalid header {}", name)),
+            }
+        }
+
+        let (sender, receiver) = mpsc::unbounded();
+
+        let spawned = EventLoopHandle::spawn(move |handle| {
+            let connector = match HttpsConnector::new(4) {
+                Ok(s) => s,
+                Err(e) => return Err(Error::Transport(format!("{}", e))),
+            };
+            let client = hyper::Client::builder().build::<_, hyper::Body>(connector);
+
+            handle.spawn(
+                receiver
+                    .map(move |(request, pending): (_, Pending)| {
+                        client
+                            .request(request)
+                            .then(move |response| Ok::<_, ()>((response, pending)))
+                    })
+                    .buffer_unordered(MAX_PARALLEL)
+                    .for_each(|(response, pending)| {
+                        let body = match response {
+                            Ok(ref s) if !s.status().is_success() => {
+                                Either::A(future::err(Error::Transport(format!(
+                                    "unexpected response status {}",
+                                    s.status()
+                                ))))
+                            }
+                            Ok(s) => Either::B(
+                                s.into_body()
+                                    .concat2()
+                                    .map_err(|e| Error::Transport(format!("{}", e))),
+                            ),
+                            Err(e) => Either::A(future::err(Error::Transport(format!("{}", e)))),
+                        };
+
+                        body.then(move |result| {
+                            let _ = pending.send(result);
+                            Ok(())
+                        })
+                    }),
+            );
+
+            Ok(())
+        });
+
+        match spawned {
+            Ok((eloop, _)) => Ok((
+                eloop,
+                Http {
+                    id: Arc::new(AtomicUsize::new(0)),
+                    url,
+                    headers,
+                    sender,
+                },
+            )),
+            Err(e) => Err(format!("unable to start event loop: {}", e)),
+        }
+    }
+}
+
+impl web3::Transport for Http {
+    type Out = Box<dyn Future<Item = rpc::Value, Error = Error> + Send>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        let id = self.id.fetch_add(1, Ordering::AcqRel);
+
+        (id, web3::helpers::build_request(id, method, params))
+    }
+
+    fn send(&self, _id: RequestId, call: rpc::Call) -> Self::Out {
+        let body = web3::helpers::to_string(&rpc::Request::Single(call));
+
+        let mut request = hyper::Request::new(hyper::Body::from(body));
+        *request.method_mut() = hyper::Method::POST;
+        *request.uri_mut() = self.url.clone();
+        request
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        for (name, value) in self.headers.iter() {
+            request.headers_mut().insert(name.clone(), value.clone());
+        }
+
+        let (pending, response) = oneshot::channel();
+        if self.sender.unbounded_send((request, pending)).is_err() {
+            return Box::new(future::err(Error::Transport(String::from(
+                "event loop stopped",
+            ))));
+        }
+
+        Box::new(
+            response
+                .map_err(|_e| Error::Transport(String::from("request cancelled")))
+                .and_then(|result| result)
+                .and_then(|body| match web3::helpers::to_response_from_slice(&body) {
+                    Ok(rpc::Response::Single(output)) => {
+                        web3::helpers::to_result_from_output(output)
+                    }
+                    Ok(_) => Err(Error::InvalidResponse(String::from(
+                        "expected a single response, got a batch",
+                    ))),
+                    Err(e) => Err(e),
+                }),
+        )
+    }
+}
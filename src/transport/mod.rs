@@ -0,0 +1,143 @@
+mod http;
+
+pub use self::http::Http;
+
+use crate::config::Endpoint;
+use jsonrpc_core as rpc;
+use std::env;
+use web3::futures::Future;
+use web3::transports::{EventLoopHandle, Ipc, WebSocket};
+use web3::{Error, RequestId};
+
+// the transport the endpoint URL asks for: http(s)://, ws(s):// or the path of an IPC socket
+#[derive(Clone, Debug)]
+pub enum Transport {
+    Http(Http),
+    WebSocket(WebSocket),
+    Ipc(Ipc),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Scheme {
+    Http,
+    WebSocket,
+    Ipc,
+}
+
+impl Transport {
+    pub fn new(endpoint: &Endpoint) -> Result<(EventLoopHandle, Self), String> {
+        let scheme = match scheme(&endpoint.url) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        // headers only make sense over http, which web3's own transports do not speak
+        if scheme != Scheme::Http && !endpoint.headers.is_empty() {
+            return Err(format!(
+                "headers and auth are only supported over http, not {}",
+                endpoint.url
+            ));
+        }
+
+        let connected = match scheme {
+            Scheme::Http => return Http::new(endpoint).map(|(e, s)| (e, Transport::Http(s))),
+            Scheme::WebSocket => {
+                WebSocket::new(&endpoint.url).map(|(e, s)| (e, Transport::WebSocket(s)))
+            }
+            Scheme::Ipc => match ipc_path(&endpoint.url) {
+                Ok(path) => Ipc::new(path).map(|(e, s)| (e, Transport::Ipc(s))),
+                Err(e) => return Err(e),
+            },
+        };
+
+        match connected {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!("unable to connect to {}: {}", endpoint.url, e)),
+        }
+    }
+}
+
+impl web3::Transport for Transport {
+    type Out = Box<dyn Future<Item = rpc::Value, Error = Error> + Send>;
+
+    fn prepare(&self, method: &str, params: Vec<rpc::Value>) -> (RequestId, rpc::Call) {
+        match self {
+            Transport::Http(s) => s.prepare(method, params),
+            Transport::WebSocket(s) => s.prepare(method, params),
+            Transport::Ipc(s) => s.prepare(method, params),
+        }
+    }
+
+    fn send(&self, id: RequestId, call: rpc::Call) -> Self::Out {
+        match self {
+            Transport::Http(s) => s.send(id, call),
+            Transport::WebSocket(s) => Box::new(s.send(id, call)),
+            Transport::Ipc(s) => Box::new(s.send(id, call)),
+        }
+    }
+}
+
+// a path is only taken as an IPC socket when absolute, e.g. ~/.ethereum/geth.ipc: anything else
+// without a scheme is most likely an http URL missing it
+fn scheme(url: &str) -> Result<Scheme, String> {
+    match url.find("://").map(|index| url[..index].to_lowercase()) {
+        Some(ref s) if s == "http" || s == "https" => Ok(Scheme::Http),
+        Some(ref s) if s == "ws" || s == "wss" => Ok(Scheme::WebSocket),
+        Some(ref s) if s == "ipc" => Ok(Scheme::Ipc),
+        Some(s) => Err(format!("unsupported ethereum endpoint scheme {}", s)),
+        None if url.starts_with('/') || url.starts_with("~/") => Ok(Scheme::Ipc),
+        None => Err(format!(
+            "missing http://, ws:// or ipc:// scheme in {}",
+            url
+        )),
+    }
+}
+
+// path of an IPC socket, a leading ~ standing for the home directory
+fn ipc_path(url: &str) -> Result<String, String> {
+    let path = url.trim_start_matches("ipc://");
+
+    if !path.starts_with("~/") {
+        return Ok(path.to_string());
+    }
+
+    match env::var("HOME") {
+        Ok(s) => Ok(format!("{}{}", s, &path[1..])),
+        Err(_e) => Err(String::from("unable to locate home directory")),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ipc_path, scheme, Scheme};
+    use std::env;
+
+    #[test]
+    fn test_scheme() {
+        assert_eq!(scheme("http://127.0.0.1:1248").unwrap(), Scheme::Http);
+        assert_eq!(scheme("HTTPS://mainnet.example.com").unwrap(), Scheme::Http);
+        assert_eq!(
+            scheme("wss://mainnet.example.com/ws").unwrap(),
+            Scheme::WebSocket
+        );
+        assert_eq!(scheme("/data/geth/geth.ipc").unwrap(), Scheme::Ipc);
+        assert_eq!(scheme("~/.ethereum/geth.ipc").unwrap(), Scheme::Ipc);
+        assert_eq!(scheme("ipc:///data/geth/geth.ipc").unwrap(), Scheme::Ipc);
+        assert!(scheme("ftp://example.com").is_err());
+        assert!(scheme("localhost:8545").is_err());
+        assert!(scheme("127.0.0.1:8545").is_err());
+        assert!(scheme("geth.ipc").is_err());
+    }
+
+    #[test]
+    fn test_ipc_path() {
+        assert_eq!(
+            ipc_path("ipc:///data/geth/geth.ipc").unwrap(),
+            "/data/geth/geth.ipc"
+        );
+        assert_eq!(
+            ipc_path("~/.ethereum/geth.ipc").unwrap(),
+            format!("{}/.ethereum/geth.ipc", env::var("HOME").unwrap())
+        );
+    }
+}
@@ -1,13 +1,14 @@
 use crate::constants;
 use crate::helpers;
+use crate::network;
+use crate::signer::Signer;
 use crate::token::Token;
 // use ethabi::Error;
-use std::str::FromStr;
 use web3::api::Web3;
 use web3::contract::Contract;
 use web3::contract::Options;
 use web3::futures::Future;
-use web3::types::{Address, Bytes, FilterBuilder, H256, U256};
+use web3::types::{Address, Bytes, CallRequest, FilterBuilder, TransactionRequest, H256, U256};
 
 
 // impl From<ethabi::Error> for Error {
@@ -22,6 +23,20 @@ use web3::types::{Address, Bytes, FilterBuilder, H256, U256};
 //     }
 // }
 
+#[derive(Clone, Debug)]
+pub enum Kind {
+    Account,
+    Contract,
+    Argent(Address),
+}
+
+#[derive(Clone, Debug)]
+pub struct LockStatus {
+    pub locked: bool,
+    pub release_after: u64,
+    pub locker: Address,
+}
+
 #[derive(Clone, Debug)]
 pub struct Wallet<'a, T: web3::Transport> {
     pub address: Address,
@@ -53,31 +68,38 @@ impl<'a, T: web3::Transport> Wallet<'a, T> {
         }
     }
 
-    pub fn guardians(&self) -> Result<Vec<Address>, String> {
-        let guardian_manager =
-            Address::from_str(&"FF5A7299ff6f0fbAad9b38906b77d08c0FBdc9A7").unwrap();
-        let guardian_manager = Contract::from_json(
-            self.web3.eth(),
-            guardian_manager,
-            constants::abis::GUARDIAN_MANAGER,
-        ).unwrap();
-        let result = guardian_manager.query("guardianStorage", (), None, Options::default(), None);
+    pub fn kind(&self) -> Result<Kind, String> {
+        let result = self.web3.eth().code(self.address, None);
 
-        let guardian_storage = match result.wait() {
+        let code = match result.wait() {
             Ok(s) => s,
-            Err(_e) => {
-                return Err(format!(
-                    "unable to fetch guardian storage address for {:?}",
-                    self.address
-                ))
-            }
+            Err(_e) => return Err(format!("unable to fetch code for {:?}", self.address)),
+        };
+
+        if code.0.is_empty() {
+            return Ok(Kind::Account);
+        }
+
+        // argent wallets are proxies exposing the BaseWallet interface
+        let result =
+            self.contract
+                .query::<U256, _, _, _>("modules", (), None, Options::default(), None);
+
+        if result.wait().is_err() {
+            return Ok(Kind::Contract);
+        }
+
+        match self.owner() {
+            Ok(s) => Ok(Kind::Argent(s)),
+            Err(_e) => Ok(Kind::Contract),
+        }
+    }
+
+    pub fn guardians(&self) -> Result<Vec<Address>, String> {
+        let guardian_storage = match self.guardian_storage() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
         };
-        let guardian_storage = Contract::from_json(
-            self.web3.eth(),
-            guardian_storage,
-            constants::abis::GUARDIAN_STORAGE,
-        )
-        .unwrap();
         let result = guardian_storage.query(
             "getGuardians",
             (self.address,),
@@ -92,6 +114,45 @@ impl<'a, T: web3::Transport> Wallet<'a, T> {
         }
     }
 
+    pub fn lock_status(&self) -> Result<LockStatus, String> {
+        let guardian_storage = match self.guardian_storage() {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        let result =
+            guardian_storage.query("isLocked", (self.address,), None, Options::default(), None);
+        let locked: bool = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to fetch lock for {:?}", self.address)),
+        };
+
+        let result =
+            guardian_storage.query("getLock", (self.address,), None, Options::default(), None);
+        let release_after: U256 = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => {
+                return Err(format!(
+                    "unable to fetch lock expiry for {:?}",
+                    self.address
+                ))
+            }
+        };
+
+        let result =
+            guardian_storage.query("getLocker", (self.address,), None, Options::default(), None);
+        let locker: Address = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => return Err(format!("unable to fetch locker for {:?}", self.address)),
+        };
+
+        Ok(LockStatus {
+            locked,
+            release_after: release_after.as_u64(),
+            locker,
+        })
+    }
+
     pub fn modules(&self) -> Result<Vec<Address>, String> {
         let mut modules = Vec::<Address>::new();
 
@@ -107,7 +168,7 @@ impl<'a, T: web3::Transport> Wallet<'a, T> {
                 None,
                 None,
             )
-            .from_block(constants::ARGENT_GENESIS_BLOCK.into())
+            .from_block(network::current().start_block.into())
             .build();
         let result = self.web3.eth().logs(filter);
 
@@ -160,41 +221,106 @@ impl<'a, T: web3::Transport> Wallet<'a, T> {
         }
     }
 
-    pub fn lock(&self) -> Result<H256, String> {
-        let accounts = match self.web3.eth().accounts().wait() {
+    pub fn lock(&self, signer: &dyn Signer) -> Result<H256, String> {
+        let request = match self.lock_request(true, signer.address()) {
             Ok(s) => s,
-            Err(_e) => return Err(String::from("unable to fetch accounts")),
+            Err(e) => return Err(e),
         };
 
-        let lock_manager = Address::from_str(&"0bc693480d447ab97aff7aa215d1586f1868cb01").unwrap();
-        let lock_manager =
-            Contract::from_json(self.web3.eth(), lock_manager, constants::abis::LOCK_MANAGER)
-                .unwrap();
+        match signer.send_transaction(request) {
+            Ok(s) => Ok(s),
+            Err(e) => Err(format!("unable to lock {:?}: {}", self.address, e)),
+        }
+    }
+
+    // eth_call of lock or unlock from the signing account: the revert reason, if any
+    pub fn simulate_lock(&self, lock: bool, signer: &dyn Signer) -> Result<Option<String>, String> {
+        let request = match self.lock_request(lock, signer.address()) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
 
-        let result = lock_manager.call("lock", (self.address,), accounts[0], Options::default());
+        let result = self.web3.eth().call(
+            CallRequest {
+                from: Some(request.from),
+                to: request.to.unwrap(),
+                gas: None,
+                gas_price: None,
+                value: None,
+                data: request.data,
+            },
+            None,
+        );
 
         match result.wait() {
+            Ok(_) => Ok(None),
+            Err(e) => Ok(Some(helpers::revert_reason(&e))),
+        }
+    }
+
+    pub fn unlock(&self, signer: &dyn Signer) -> Result<H256, String> {
+        let request = match self.lock_request(false, signer.address()) {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+
+        match signer.send_transaction(request) {
             Ok(s) => Ok(s),
-            Err(_e) => Err(format!("unable to lock {:?}", self.address)),
+            Err(e) => Err(format!("unable to unlock {:?}: {}", self.address, e)),
         }
     }
 
-    pub fn unlock(&self) -> Result<H256, String> {
-        let accounts = match self.web3.eth().accounts().wait() {
+    fn lock_request(&self, lock: bool, from: Address) -> Result<TransactionRequest, String> {
+        let lock_manager = match network::current().module("LockManager") {
             Ok(s) => s,
-            Err(_e) => return Err(String::from("unable to fetch accounts")),
+            Err(e) => return Err(e),
         };
+        let abi = ethabi::Contract::load(constants::abis::LOCK_MANAGER).unwrap();
+        let function = abi.function(if lock { "lock" } else { "unlock" }).unwrap();
+        let data = function
+            .encode_input(&[ethabi::Token::Address(self.address)])
+            .unwrap();
 
-        let lock_manager = Address::from_str(&"0bc693480d447ab97aff7aa215d1586f1868cb01").unwrap();
-        let lock_manager =
-            Contract::from_json(self.web3.eth(), lock_manager, constants::abis::LOCK_MANAGER)
-                .unwrap();
+        Ok(TransactionRequest {
+            from,
+            to: Some(lock_manager),
+            gas: None,
+            gas_price: None,
+            value: None,
+            nonce: None,
+            data: Some(Bytes(data)),
+            condition: None,
+        })
+    }
+
+    fn guardian_storage(&self) -> Result<Contract<T>, String> {
+        let guardian_manager = match network::current().module("GuardianManager") {
+            Ok(s) => s,
+            Err(e) => return Err(e),
+        };
+        let guardian_manager = Contract::from_json(
+            self.web3.eth(),
+            guardian_manager,
+            constants::abis::GUARDIAN_MANAGER,
+        )
+        .unwrap();
+        let result = guardian_manager.query("guardianStorage", (), None, Options::default(), None);
 
-        let result = lock_manager.call("unlock", (self.address,), accounts[0], Options::default());
+        let guardian_storage = match result.wait() {
+            Ok(s) => s,
+            Err(_e) => {
+                return Err(format!(
+                    "unable to fetch guardian storage address for {:?}",
+                    self.address
+                ))
+            }
+        };
 
-        match result.wait() {
-            Ok(s) => Ok(s),
-            Err(_e) => Err(format!("unable to unlock {:?}", self.address)),
-        }
+        Ok(Contract::from_json(
+            self.web3.eth(),
+            guardian_storage,
+            constants::abis::GUARDIAN_STORAGE,
+        )
+        .unwrap())
     }
 }